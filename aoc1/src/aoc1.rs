@@ -3,7 +3,7 @@ use aoc1::first_and_last;
 fn main() {
     println!("Testing");
 
-    let total = include_str!("../input.txt")
+    let total = runner::load_input(include_str!("../input.txt"))
         .split('\n')
         .map(aoc1::DigitIterator::new)
         .map(|d| first_and_last(d.iterate_digits()))
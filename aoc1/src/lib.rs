@@ -1,3 +1,5 @@
+use std::io::{self, BufRead};
+
 struct Mapping<'a> {
     prefixes: &'a [&'a str],
     value: i32,
@@ -79,12 +81,38 @@ impl<'a> DigitIterator<'a> {
     ///
     /// ```
     pub fn iterate_digits(self) -> impl Iterator<Item = i32> + 'a {
+        self.digits(false)
+    }
+
+    /// Iterate only numeric digits within a string, ignoring spelled out ones
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use aoc1::DigitIterator;
+    /// use itertools::assert_equal;
+    ///
+    /// assert_equal(DigitIterator::new("a1b2c3d4e5f").iterate_numeric_digits(), [1,2,3,4,5]);
+    /// assert_equal(DigitIterator::new("two1nine").iterate_numeric_digits(), [1]);
+    /// ```
+    pub fn iterate_numeric_digits(self) -> impl Iterator<Item = i32> + 'a {
+        self.digits(true)
+    }
+
+    fn digits(self, numeric_only: bool) -> impl Iterator<Item = i32> + 'a {
+        // first prefix of every mapping is the numeric one
+        let prefix_count = if numeric_only { 1 } else { usize::MAX };
+
         self.data
             .char_indices()
             .map(|index| &self.data[index.0..])
-            .filter_map(|tail| {
+            .filter_map(move |tail| {
                 for &Mapping { prefixes, value } in NAME_MAP {
-                    if prefixes.iter().any(|p| tail.starts_with(p)) {
+                    if prefixes
+                        .iter()
+                        .take(prefix_count)
+                        .any(|p| tail.starts_with(p))
+                    {
                         return Some(value);
                     }
                 }
@@ -114,9 +142,51 @@ pub fn first_and_last(i: impl IntoIterator<Item = i32>) -> Option<(i32, i32)> {
         .map(|first| (first, iter.last().unwrap_or(first)))
 }
 
+/// Calibration value of a single line: first and last digit combined into a
+/// two digit number. Lines without digits have a value of 0.
+fn calibration_value(line: &str, numeric_only: bool) -> i32 {
+    first_and_last(DigitIterator::new(line).digits(numeric_only))
+        .map(|(first, last)| first * 10 + last)
+        .unwrap_or(0)
+}
+
+/// Sums the calibration values of all lines, reading them one by one.
+///
+/// When `numeric_only` is true, spelled out digits are ignored.
+pub fn sum_calibration<R: BufRead>(r: R, numeric_only: bool) -> io::Result<i32> {
+    let mut total = 0;
+    for line in r.lines() {
+        total += calibration_value(&line?, numeric_only);
+    }
+    Ok(total)
+}
+
+pub fn part1(input: &str) -> i32 {
+    input.split('\n').map(|l| calibration_value(l, true)).sum()
+}
+
+pub fn part2(input: &str) -> i32 {
+    input.split('\n').map(|l| calibration_value(l, false)).sum()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{first_and_last, DigitIterator};
+    use std::io::Cursor;
+
+    use crate::{first_and_last, part1, part2, sum_calibration, DigitIterator};
+
+    const EXAMPLE1: &str = "1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+    const EXAMPLE2: &str = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
 
     #[test]
     fn test_mapping() {
@@ -127,4 +197,28 @@ mod tests {
             Some((8, 9))
         );
     }
+
+    #[test]
+    fn test_parts() {
+        assert_eq!(part1(EXAMPLE1), 142);
+        assert_eq!(part2(EXAMPLE2), 281);
+    }
+
+    #[test]
+    fn test_sum_calibration() {
+        assert_eq!(
+            sum_calibration(Cursor::new(EXAMPLE1), true).expect("valid"),
+            142
+        );
+        assert_eq!(
+            sum_calibration(Cursor::new(EXAMPLE2), false).expect("valid"),
+            281
+        );
+
+        // spelled out digits are ignored, lines without digits count as 0
+        assert_eq!(
+            sum_calibration(Cursor::new(EXAMPLE2), true).expect("valid"),
+            11 + 22 + 33 + 42 + 24 + 77
+        );
+    }
 }
@@ -17,9 +17,9 @@ fn main() {
         .with(stdout_log.with_filter(LevelFilter::WARN))
         .init();
 
-    let s1 = part1(include_str!("../input.txt"));
-    println!("Part 1: {}", s1);
-
-    let s2 = part2(include_str!("../input.txt"));
-    println!("Part 2: {}", s2);
+    runner::run(
+        include_str!("../input.txt"),
+        |s| part1(s).expect("valid input"),
+        |s| part2(s).expect("valid input"),
+    );
 }
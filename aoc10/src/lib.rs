@@ -117,9 +117,17 @@ fn parse_line(input: &str) -> IResult<&str, Line> {
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
-struct Point {
-    row: usize,
-    col: usize,
+pub struct Point {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Where a tile is relative to the main loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Classification {
+    Loop,
+    Inside,
+    Outside,
 }
 
 #[derive(PartialEq, PartialOrd, Clone)]
@@ -186,58 +194,69 @@ impl Map {
         }
     }
 
-    fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> {
-        let mut result = Vec::new();
+    fn step(&self, p: Point, d: Direction) -> Option<Point> {
+        match d {
+            Direction::Left => self.left(p),
+            Direction::Right => self.right(p),
+            Direction::Up => self.up(p),
+            Direction::Down => self.down(p),
+        }
+    }
 
-        let value = match self.at(point) {
+    /// Directions in which `p` connects to a compatible pipe
+    /// (i.e. the neighbour in that direction connects back).
+    fn connected_directions(&self, p: Point) -> Vec<Direction> {
+        let value = match self.at(p) {
             Some(v) => v,
-            None => return result.into_iter(),
+            None => return Vec::new(),
         };
 
-        if let Some(x) = self.left(point) {
-            if let Some(r) = self.at(x) {
-                if value.right_of(r) {
-                    result.push(x);
-                }
-            }
-        }
-
-        if let Some(x) = self.right(point) {
-            if let Some(r) = self.at(x) {
-                if value.left_of(r) {
-                    result.push(x);
-                }
-            }
-        }
-
-        if let Some(x) = self.up(point) {
-            if let Some(r) = self.at(x) {
-                if value.below(r) {
-                    result.push(x);
-                }
+        [
+            Direction::Left,
+            Direction::Right,
+            Direction::Up,
+            Direction::Down,
+        ]
+        .into_iter()
+        .filter(|d| {
+            let other = match self.step(p, *d).and_then(|x| self.at(x)) {
+                Some(other) => other,
+                None => return false,
+            };
+            match d {
+                Direction::Left => value.right_of(other),
+                Direction::Right => value.left_of(other),
+                Direction::Up => value.below(other),
+                Direction::Down => value.above(other),
             }
-        }
-
-        if let Some(x) = self.down(point) {
-            if let Some(r) = self.at(x) {
-                if value.above(r) {
-                    result.push(x);
-                }
-            }
-        }
+        })
+        .collect()
+    }
 
-        result.into_iter()
+    fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.connected_directions(point)
+            .into_iter()
+            .filter_map(move |d| self.step(point, d))
     }
 
-    fn start_point(&self) -> Option<Point> {
-        for (row, line) in self.lines.iter().enumerate() {
-            for (col, item) in line.points.iter().enumerate() {
-                if *item == MapPoint::Start {
-                    return Some(Point { row, col });
-                }
-            }
+    /// Finds the start point, requiring exactly one in the map
+    fn start_point(&self) -> Result<Point, String> {
+        let mut starts = self.lines.iter().enumerate().flat_map(|(row, line)| {
+            line.points
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| **item == MapPoint::Start)
+                .map(move |(col, _)| Point { row, col })
+        });
+
+        match (starts.next(), starts.next()) {
+            (Some(start), None) => Ok(start),
+            (None, _) => Err("Map has no start point".into()),
+            (Some(first), Some(second)) => Err(format!(
+                "Map has multiple start points: {:?} and {:?}",
+                first, second
+            )),
         }
-        None
     }
 
     fn in_loop(&self, p: Point) -> bool {
@@ -251,7 +270,7 @@ impl Map {
 
         processing.push_back((
             self.start_point()
-                .expect("valid input should have a start point"),
+                .expect("start point validated by parse_input"),
             0u32,
         ));
 
@@ -298,60 +317,99 @@ impl Map {
         }
     }
 
+    /// The pipe hidden under the start point, if it connects to exactly
+    /// two neighbours.
+    fn start_pipe(&self) -> Option<MapPoint> {
+        let start = self.start_point().ok()?;
+        match self.connected_directions(start).as_slice() {
+            [a, b] => Some(MapPoint::Pipe(*a, *b)),
+            _ => None,
+        }
+    }
+
+    /// Draws the map using box-drawing characters, with the start point
+    /// replaced by its pipe.
+    fn render(&self) -> String {
+        let start = self.start_pipe();
+        let mut result = String::new();
+
+        for line in self.lines.iter() {
+            for p in line.points.iter() {
+                result.push(match (p, start) {
+                    (MapPoint::Start, Some(pipe)) => pipe.graphic_char(),
+                    _ => p.graphic_char(),
+                });
+            }
+            result.push('\n');
+        }
+
+        result
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn inside_outside(&self) -> u32 {
+        self.classify()
+            .values()
+            .filter(|c| **c == Classification::Inside)
+            .count() as u32
+    }
+
+    fn classify(&self) -> HashMap<Point, Classification> {
         // only things in the main loop will be relevant
         let distances = self.distances();
+        let mut result = HashMap::new();
 
-        let total = self
-            .lines
-            .iter()
-            .enumerate()
-            .map(|(row, line)| {
-                // logic:
-                //   paritition scan for lines:
-                //   odd up/down we are inside, even up/down we are outside
-                let mut up = false;
-                let mut down = false;
-                let mut inside = 0u32;
-
-                debug!("Checking line {:?}", line);
-
-                for (col, p) in line.points.iter().enumerate() {
-                    if distances.contains_key(&Point { row, col }) {
-                        debug!("Contains: {},{}", row, col);
-                        if *p == MapPoint::Start {
-                            debug!("   DEBUG start point: {},{}", row, col);
-                            // FIXME: now what? Figure out where to start
-                            for n in self.neighbours(Point { row, col }) {
-                                if self.at(n).expect("ok").above(*p) {
+        for (row, line) in self.lines.iter().enumerate() {
+            // logic:
+            //   paritition scan for lines:
+            //   odd up/down we are inside, even up/down we are outside
+            let mut up = false;
+            let mut down = false;
+            let mut inside = 0u32;
+
+            debug!("Checking line {:?}", line);
+
+            for (col, p) in line.points.iter().enumerate() {
+                let classification = if distances.contains_key(&Point { row, col }) {
+                    debug!("Contains: {},{}", row, col);
+                    if *p == MapPoint::Start {
+                        debug!("   DEBUG start point: {},{}", row, col);
+                        for d in self.connected_directions(Point { row, col }) {
+                            match d {
+                                Direction::Up => {
                                     debug!("    ABOVE");
                                     up = !up;
                                 }
-                                if self.at(n).expect("ok").below(*p) {
+                                Direction::Down => {
                                     debug!("    BELOW");
                                     down = !down;
                                 }
+                                _ => {}
                             }
-                        } else {
-                            if p.has_connection(Direction::Down) {
-                                down = !down;
-                            }
-                            if p.has_connection(Direction::Up) {
-                                up = !up;
-                            }
                         }
-                    } else if up && down {
-                        debug!("Add inside: {},{}", row, col);
-                        inside += 1;
+                    } else {
+                        if p.has_connection(Direction::Down) {
+                            down = !down;
+                        }
+                        if p.has_connection(Direction::Up) {
+                            up = !up;
+                        }
                     }
-                }
-                debug!("  Inside: {}", inside);
-                inside
-            })
-            .sum();
+                    Classification::Loop
+                } else if up && down {
+                    debug!("Add inside: {},{}", row, col);
+                    inside += 1;
+                    Classification::Inside
+                } else {
+                    Classification::Outside
+                };
+
+                result.insert(Point { row, col }, classification);
+            }
+            debug!("  Inside: {}", inside);
+        }
 
-        total
+        result
     }
 }
 
@@ -361,29 +419,52 @@ fn parse_map(input: &str) -> IResult<&str, Map> {
         .parse(input)
 }
 
-pub fn part1(input: &str) -> u32 {
-    let (r, map) = parse_map(input).expect("valid input");
-    assert_eq!(r, "");
+/// Parses the map, validating that it has exactly one start point
+fn parse_input(input: &str) -> Result<Map, String> {
+    let (r, map) = parse_map(input).map_err(|e| format!("Error parsing: {:?}", e))?;
+    if !r.is_empty() {
+        return Err(format!("Unparsed input: {:?}", r));
+    }
+    map.start_point()?;
+
+    Ok(map)
+}
+
+/// The loop point farthest away from the start, together with its distance.
+pub fn farthest_point(input: &str) -> Result<(Point, u32), String> {
+    let map = parse_input(input)?;
 
     let distances = map.distances();
 
     distances
-        .iter()
-        .filter(|(k, _)| map.in_loop(**k))
-        .map(|(_, v)| *v)
-        .max()
-        .expect("some data")
+        .into_iter()
+        .filter(|(k, _)| map.in_loop(*k))
+        .max_by_key(|(k, v)| (*v, *k))
+        .ok_or("Start point is not part of a loop".into())
+}
+
+/// Draws the main loop using box-drawing characters, with everything
+/// that is not part of the loop shown as ground.
+pub fn render_loop(input: &str) -> Result<String, String> {
+    Ok(parse_input(input)?.as_loop_only().render())
+}
+
+/// Classifies every tile of the map as part of the main loop, inside it
+/// or outside of it.
+pub fn classify(input: &str) -> Result<HashMap<Point, Classification>, String> {
+    Ok(parse_input(input)?.as_loop_only().classify())
 }
 
-pub fn part2(input: &str) -> u32 {
-    let (r, map) = parse_map(input).expect("valid input");
-    assert_eq!(r, "");
+pub fn part1(input: &str) -> Result<u32, String> {
+    farthest_point(input).map(|(_, distance)| distance)
+}
 
-    let map = map.as_loop_only();
+pub fn part2(input: &str) -> Result<u32, String> {
+    let map = parse_input(input)?.as_loop_only();
 
     tracing::info!("{:?}", &map);
 
-    map.inside_outside()
+    Ok(map.inside_outside())
 }
 
 #[cfg(test)]
@@ -392,14 +473,101 @@ mod tests {
 
     #[test_log::test]
     fn test_part1() {
-        assert_eq!(part1(include_str!("../example1.txt")), 4);
-        assert_eq!(part1(include_str!("../example2.txt")), 8);
+        assert_eq!(part1(include_str!("../example1.txt")), Ok(4));
+        assert_eq!(part1(include_str!("../example2.txt")), Ok(8));
+    }
+
+    #[test_log::test]
+    fn test_farthest_point() {
+        let (point, distance) = farthest_point(include_str!("../example2.txt")).expect("valid");
+        assert_eq!(distance, 8);
+        assert_eq!(point, Point { row: 2, col: 4 });
+
+        let map = parse_input(include_str!("../example2.txt")).expect("valid");
+        assert!(map.in_loop(point));
     }
 
     #[test_log::test]
     fn test_part2() {
-        assert_eq!(part2(include_str!("../example_inside_outside_1.txt")), 4);
-        assert_eq!(part2(include_str!("../example_inside_outside_2.txt")), 8);
-        assert_eq!(part2(include_str!("../example_inside_outside_3.txt")), 10);
+        assert_eq!(
+            part2(include_str!("../example_inside_outside_1.txt")),
+            Ok(4)
+        );
+        assert_eq!(
+            part2(include_str!("../example_inside_outside_2.txt")),
+            Ok(8)
+        );
+        assert_eq!(
+            part2(include_str!("../example_inside_outside_3.txt")),
+            Ok(10)
+        );
+    }
+
+    #[test_log::test]
+    fn test_render_loop() {
+        let rendered = render_loop(include_str!("../example1.txt")).expect("valid");
+        assert_eq!(rendered, ".....\n.┌─┐.\n.│.│.\n.└─┘.\n.....\n");
+
+        // pipes outside the loop are not rendered
+        let rendered = render_loop("-L|F7\n7S-7|\nL|7||\n-L-J|\nL|-JF").expect("valid");
+        assert_eq!(rendered, ".....\n.┌─┐.\n.│.│.\n.└─┘.\n.....\n");
+    }
+
+    #[test_log::test]
+    fn test_classify() {
+        let input = include_str!("../example_inside_outside_1.txt");
+        let classes = classify(input).expect("valid");
+
+        let count = |c: Classification| classes.values().filter(|x| **x == c).count();
+        assert_eq!(count(Classification::Inside), 4);
+        assert_eq!(
+            count(Classification::Inside) as u32,
+            part2(input).expect("valid")
+        );
+        assert_eq!(
+            classes.get(&Point { row: 6, col: 2 }),
+            Some(&Classification::Inside)
+        );
+        assert_eq!(
+            classes.get(&Point { row: 0, col: 0 }),
+            Some(&Classification::Outside)
+        );
+        assert_eq!(
+            classes.get(&Point { row: 1, col: 1 }),
+            Some(&Classification::Loop)
+        );
+
+        let rows = input.lines().count();
+        let cols = input.lines().next().expect("has lines").len();
+        assert_eq!(classes.len(), rows * cols);
+    }
+
+    #[test_log::test]
+    fn test_start_validation() {
+        let two_starts = ".....\n.S-7.\n.|.|.\n.L-S.\n.....";
+        assert!(part1(two_starts)
+            .expect_err("multiple starts")
+            .contains("multiple start points"));
+        assert!(part2(two_starts).is_err());
+
+        let no_start = ".....\n.F-7.\n.|.|.\n.L-J.\n.....";
+        assert_eq!(part1(no_start), Err("Map has no start point".into()));
+        assert_eq!(part2(no_start), Err("Map has no start point".into()));
+    }
+
+    #[test_log::test]
+    fn test_connected_directions() {
+        let map = parse_input(include_str!("../example1.txt")).expect("valid input");
+        let start = map.start_point().expect("has start");
+
+        assert_eq!(
+            map.connected_directions(start),
+            vec![Direction::Right, Direction::Down]
+        );
+        assert_eq!(
+            map.connected_directions(Point { row: 1, col: 2 }),
+            vec![Direction::Left, Direction::Right]
+        );
+        assert_eq!(map.connected_directions(Point { row: 0, col: 0 }), vec![]);
     }
 }
@@ -20,7 +20,13 @@ pub struct Position {
     col: u64,
 }
 
-#[derive(Debug, PartialEq, PartialOrd)]
+impl Position {
+    fn distance(&self, other: &Position) -> u64 {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+}
+
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
 pub struct Universe {
     galaxies: BTreeSet<Position>,
 }
@@ -93,27 +99,32 @@ impl Universe {
             .combinations(2)
             .map(|c| {
                 assert_eq!(c.len(), 2);
-                let p1 = c.get(0).expect("valid");
+                let p1 = c.first().expect("valid");
                 let p2 = c.get(1).expect("valid");
 
-                let dr = if p1.row < p2.row {
-                    p2.row - p1.row
-                } else {
-                    p1.row - p2.row
-                };
-
-                let dc = if p1.col < p2.col {
-                    p2.col - p1.col
-                } else {
-                    p1.col - p2.col
-                };
+                let d = p1.distance(p2);
+                debug!("From {:?} to {:?} => {}", p1, p2, d);
 
-                debug!("From {:?} to {:?} => {}", p1, p2, (dr + dc));
-
-                dr + dc
+                d
             })
             .sum()
     }
+
+    /// Pairwise distances between galaxies after every empty row and
+    /// column becomes `factor` rows/columns wide.
+    ///
+    /// Galaxies are indexed in reading order (row, then column).
+    pub fn distance_matrix(&self, factor: u64) -> Vec<Vec<u64>> {
+        assert!(factor > 0, "expansion factor must be positive");
+        let mut expanded = self.clone();
+        expanded.expand(factor - 1);
+
+        expanded
+            .galaxies
+            .iter()
+            .map(|a| expanded.galaxies.iter().map(|b| a.distance(b)).collect())
+            .collect()
+    }
 }
 
 pub fn universe(span: Span) -> Universe {
@@ -149,12 +160,19 @@ pub fn part_expand(input: &str, amount: u64) -> u64 {
     u.all_distances()
 }
 
+/// Sum of all galaxy distances, where every empty row and column is
+/// replaced by `factor` empty rows/columns.
+pub fn solve(input: &str, factor: u64) -> u64 {
+    assert!(factor > 0, "expansion factor must be positive");
+    part_expand(input, factor - 1)
+}
+
 pub fn part1(input: &str) -> u64 {
-    part_expand(input, 1)
+    solve(input, 2)
 }
 
 pub fn part2(input: &str) -> u64 {
-    part_expand(input, 1000000 - 1)
+    solve(input, 1000000)
 }
 
 #[cfg(test)]
@@ -197,6 +215,39 @@ mod tests {
     fn test_part2() {
         assert_eq!(part_expand(include_str!("../example.txt"), 10 - 1), 1030);
         assert_eq!(part_expand(include_str!("../example.txt"), 100 - 1), 8410);
+
+        assert_eq!(part2(include_str!("../example.txt")), 82000210);
+    }
+
+    #[test_log::test]
+    fn test_solve() {
+        let example = include_str!("../example.txt");
+
+        assert_eq!(solve(example, 2), part1(example));
+        assert_eq!(solve(example, 10), 1030);
+        assert_eq!(solve(example, 100), 8410);
+        assert_eq!(solve(example, 1000000), part2(example));
+    }
+
+    #[test_log::test]
+    fn test_distance_matrix() {
+        let m = universe(include_str!("../example.txt").into()).distance_matrix(2);
+
+        assert_eq!(m.len(), 9);
+
+        // pairs documented in the puzzle (1-based galaxy numbers)
+        assert_eq!(m[4][8], 9); // 5 to 9
+        assert_eq!(m[0][6], 15); // 1 to 7
+        assert_eq!(m[2][5], 17); // 3 to 6
+        assert_eq!(m[7][8], 5); // 8 to 9
+        assert_eq!(m[8][7], 5);
+        assert_eq!(m[3][3], 0);
+
+        // every pair counted once
+        assert_eq!(
+            m.iter().flatten().sum::<u64>(),
+            2 * part1(include_str!("../example.txt"))
+        );
     }
 
     #[test_log::test]
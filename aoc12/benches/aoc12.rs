@@ -14,3 +14,8 @@ fn part1() {
 fn part2() {
     aoc12::part2(black_box(include_str!("../input.txt")));
 }
+
+#[divan::bench]
+fn part2_cache_per_line() {
+    aoc12::part2_cache_per_line(black_box(include_str!("../input.txt")));
+}
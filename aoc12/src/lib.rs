@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, fmt::Write};
+use std::{collections::HashMap, fmt::Write};
 
 use nom::{
     branch::alt,
@@ -10,7 +10,7 @@ use nom::{
     IResult, Parser,
 };
 
-#[derive(Ord, PartialOrd, PartialEq, Eq, Copy, Clone)]
+#[derive(Ord, PartialOrd, PartialEq, Eq, Copy, Clone, Hash)]
 pub enum SpringState {
     Operational,
     Damaged,
@@ -27,6 +27,11 @@ impl std::fmt::Debug for SpringState {
     }
 }
 
+/// Renders states the same way as the input, e.g. `#.#.###`
+pub fn render_arrangement(states: &[SpringState]) -> String {
+    states.iter().map(|s| format!("{:?}", s)).collect()
+}
+
 fn spring_state(input: &str) -> IResult<&str, SpringState> {
     alt((
         value(SpringState::Operational, tag(".")),
@@ -51,7 +56,7 @@ fn consume_damage(input: &[SpringState], amount: usize) -> Option<&[SpringState]
 
     let (left, right) = input.split_at(amount);
 
-    if left.iter().any(|s| *s == SpringState::Operational) {
+    if left.contains(&SpringState::Operational) {
         // insufficient run
         return None;
     }
@@ -68,30 +73,88 @@ fn consume_damage(input: &[SpringState], amount: usize) -> Option<&[SpringState]
     }
 }
 
-struct MatchMemoization {
-    state: BTreeMap<(usize, usize), u64>, // map (len of state, len of runs) -> possibilities
+/// Quick check that `runs` could fit into `states` at all: the runs and
+/// the gaps between them must fit in the line, and the damaged springs
+/// must be coverable by the runs (and vice versa).
+fn runs_feasible(states: &[SpringState], runs: &[u64]) -> bool {
+    let damaged = runs.iter().sum::<u64>() as usize;
+    let gaps = runs.len().saturating_sub(1);
+    if damaged + gaps > states.len() {
+        return false;
+    }
+
+    let forced = states
+        .iter()
+        .filter(|s| **s == SpringState::Damaged)
+        .count();
+    let unknown = states
+        .iter()
+        .filter(|s| **s == SpringState::Unknown)
+        .count();
+
+    forced <= damaged && damaged <= forced + unknown
+}
+
+/// Caches possibilities for (states, runs) suffixes.
+///
+/// Keys are the actual suffix contents, so the same cache can be reused
+/// across different lines.
+///
+/// ```
+/// use aoc12::{MatchMemoization, SpringState};
+///
+/// // ???.### 1,1,3
+/// let states = [
+///     SpringState::Unknown,
+///     SpringState::Unknown,
+///     SpringState::Unknown,
+///     SpringState::Operational,
+///     SpringState::Damaged,
+///     SpringState::Damaged,
+///     SpringState::Damaged,
+/// ];
+///
+/// let mut memo = MatchMemoization::new();
+/// assert_eq!(memo.match_possibilities(&states, &[1, 1, 3]), 1);
+/// assert_eq!(memo.match_possibilities(&states[..3], &[1]), 3);
+/// ```
+#[derive(Default)]
+pub struct MatchMemoization {
+    state: HashMap<Vec<SpringState>, HashMap<Vec<u64>, u64>>, // map states -> runs -> possibilities
 }
 
 impl MatchMemoization {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
-            state: BTreeMap::new(),
+            state: HashMap::new(),
         }
     }
 
-    fn match_possibilities(&mut self, states: &[SpringState], runs: &[u64]) -> u64 {
-        let key = (states.len(), runs.len());
-        if let Some(value) = self.state.get(&key) {
-            return *value;
+    fn get(&self, states: &[SpringState], runs: &[u64]) -> Option<u64> {
+        self.state.get(states)?.get(runs).copied()
+    }
+
+    fn insert(&mut self, states: &[SpringState], runs: &[u64], total: u64) {
+        self.state
+            .entry(states.to_vec())
+            .or_default()
+            .insert(runs.to_vec(), total);
+    }
+
+    /// Number of ways the unknown `states` can be filled in so that the
+    /// damaged springs form exactly the given `runs`.
+    pub fn match_possibilities(&mut self, states: &[SpringState], runs: &[u64]) -> u64 {
+        if let Some(value) = self.get(states, runs) {
+            return value;
         }
         match runs {
             [] => {
-                let total = if states.iter().any(|s| *s == SpringState::Damaged) {
+                let total = if states.contains(&SpringState::Damaged) {
                     0
                 } else {
                     1
                 };
-                self.state.insert(key, total);
+                self.insert(states, runs, total);
                 total
             }
             [first, tail_runs @ ..] => {
@@ -111,16 +174,85 @@ impl MatchMemoization {
                     }
                 }
 
-                self.state.insert(key, total);
+                self.insert(states, runs, total);
                 total
             }
         }
     }
 }
 
+/// Appends to `out` every arrangement of `states` matching `runs`, each
+/// prefixed by `prefix`.
+fn fill_arrangements(
+    states: &[SpringState],
+    runs: &[u64],
+    prefix: &mut Vec<SpringState>,
+    out: &mut Vec<Vec<SpringState>>,
+) {
+    let Some((first, tail_runs)) = runs.split_first() else {
+        if !states.contains(&SpringState::Damaged) {
+            let mut arrangement = prefix.clone();
+            arrangement.extend(states.iter().map(|s| match s {
+                SpringState::Unknown => SpringState::Operational,
+                other => *other,
+            }));
+            out.push(arrangement);
+        }
+        return;
+    };
+
+    let len = prefix.len();
+
+    if let Some(tail_states) = consume_damage(states, *first as usize) {
+        prefix.resize(len + *first as usize, SpringState::Damaged);
+        if tail_states.len() < states.len() - *first as usize {
+            // a separator was consumed as well
+            prefix.push(SpringState::Operational);
+        }
+        fill_arrangements(tail_states, tail_runs, prefix, out);
+        prefix.truncate(len);
+    }
+
+    match states {
+        [] | [SpringState::Damaged, ..] => (),
+        [_, tail_states @ ..] => {
+            prefix.push(SpringState::Operational);
+            fill_arrangements(tail_states, runs, prefix, out);
+            prefix.truncate(len);
+        }
+    }
+}
+
 impl SpringLine {
+    /// Every possible way of filling in the unknown states.
+    ///
+    /// The result grows exponentially with the number of unknowns, see
+    /// [SpringLine::arrangements_limited] for a guarded version.
+    pub fn arrangements(&self) -> Vec<Vec<SpringState>> {
+        let mut out = Vec::new();
+        fill_arrangements(&self.states, &self.runs, &mut Vec::new(), &mut out);
+        out
+    }
+
+    /// Same as [SpringLine::arrangements], however only enumerates if there
+    /// are at most `max` arrangements. Returns `Err(count)` otherwise.
+    pub fn arrangements_limited(&self, max: usize) -> Result<Vec<Vec<SpringState>>, u64> {
+        let count = self.possibilities();
+        if count > max as u64 {
+            return Err(count);
+        }
+        Ok(self.arrangements())
+    }
+
     fn possibilities(&self) -> u64 {
-        MatchMemoization::new().match_possibilities(self.states.as_slice(), self.runs.as_slice())
+        self.possibilities_with(&mut MatchMemoization::new())
+    }
+
+    fn possibilities_with(&self, memo: &mut MatchMemoization) -> u64 {
+        if !runs_feasible(&self.states, &self.runs) {
+            return 0;
+        }
+        memo.match_possibilities(self.states.as_slice(), self.runs.as_slice())
     }
 
     fn unfold(self) -> Self {
@@ -168,17 +300,36 @@ fn parse_input(i: &str) -> IResult<&str, Input> {
         .parse(i)
 }
 
+impl Input {
+    /// Total possibilities, with a single cache shared by all lines
+    fn possibilities(&self) -> u64 {
+        let mut memo = MatchMemoization::new();
+        self.lines
+            .iter()
+            .map(|l| l.possibilities_with(&mut memo))
+            .sum()
+    }
+}
+
 pub fn part1(i: &str) -> u64 {
     let (r, d) = parse_input(i).expect("valid input");
     assert_eq!(r, "");
 
-    d.lines.iter().map(|l| l.possibilities()).sum()
+    d.possibilities()
 }
 
 pub fn part2(i: &str) -> u64 {
     let (r, d) = parse_input(i).expect("valid input");
     assert_eq!(r, "");
 
+    d.unfold().possibilities()
+}
+
+/// Same as [part2], however every line uses its own cache
+pub fn part2_cache_per_line(i: &str) -> u64 {
+    let (r, d) = parse_input(i).expect("valid input");
+    assert_eq!(r, "");
+
     d.unfold().lines.iter().map(|l| l.possibilities()).sum()
 }
 
@@ -186,6 +337,49 @@ pub fn part2(i: &str) -> u64 {
 mod tests {
     use super::*;
 
+    /// Counts arrangements by trying every assignment of the unknown
+    /// states. Only usable for a small number of unknowns.
+    fn brute_force_possibilities(line: &SpringLine) -> u64 {
+        let unknown = line
+            .states
+            .iter()
+            .filter(|s| **s == SpringState::Unknown)
+            .count();
+        assert!(unknown < 32, "too many unknowns for brute force");
+
+        let mut total = 0;
+        for mask in 0..(1_u64 << unknown) {
+            let mut runs = Vec::new();
+            let mut current = 0;
+            let mut bit = 0;
+
+            for s in line.states.iter() {
+                let damaged = match s {
+                    SpringState::Operational => false,
+                    SpringState::Damaged => true,
+                    SpringState::Unknown => {
+                        bit += 1;
+                        mask & (1 << (bit - 1)) != 0
+                    }
+                };
+                if damaged {
+                    current += 1;
+                } else if current > 0 {
+                    runs.push(current);
+                    current = 0;
+                }
+            }
+            if current > 0 {
+                runs.push(current);
+            }
+
+            if runs == line.runs {
+                total += 1;
+            }
+        }
+        total
+    }
+
     fn spring_line_items(s: &str) -> Vec<SpringState> {
         let (s, r) = many1(spring_state)(s).expect("valid input");
         assert_eq!(s, "");
@@ -193,6 +387,29 @@ mod tests {
         r
     }
 
+    #[test]
+    fn test_runs_feasible() {
+        let line = |s: &str| spring_line(s).expect("valid line").1;
+
+        for (text, feasible) in [
+            ("???.### 1,1,3", true),
+            ("... 0", true),
+            ("?.# 1", true),
+            ("??? 2,1", false), // needs 4 springs
+            ("#.# 1", false),   // too many damaged
+            ("#.. 2", false),   // not enough unknowns
+        ] {
+            let l = line(text);
+            assert_eq!(runs_feasible(&l.states, &l.runs), feasible, "{}", text);
+        }
+        assert!(runs_feasible(&[SpringState::Unknown], &[]));
+        assert!(!runs_feasible(&[SpringState::Damaged], &[]));
+
+        let mut memo = MatchMemoization::new();
+        assert_eq!(line("?#?.?? 3,2,1").possibilities_with(&mut memo), 0);
+        assert!(memo.state.is_empty());
+    }
+
     #[test]
     fn test_consume_damage() {
         assert_eq!(
@@ -314,6 +531,89 @@ mod tests {
         assert_eq!(part2(include_str!("../example.txt")), 525152);
     }
 
+    #[test]
+    fn test_shared_cache() {
+        assert_eq!(
+            part2_cache_per_line(include_str!("../example.txt")),
+            part2(include_str!("../example.txt"))
+        );
+    }
+
+    #[test]
+    fn test_brute_force() {
+        let (_, d) = parse_input(include_str!("../example.txt")).expect("valid");
+
+        for line in d.lines.iter() {
+            assert_eq!(line.possibilities(), brute_force_possibilities(line));
+        }
+
+        // unfolded lines grow fast, only check the ones with few unknowns
+        for line in d.unfold().lines.iter().filter(|l| {
+            l.states
+                .iter()
+                .filter(|s| **s == SpringState::Unknown)
+                .count()
+                <= 20
+        }) {
+            assert_eq!(line.possibilities(), brute_force_possibilities(line));
+        }
+    }
+
+    #[test]
+    fn test_arrangements() {
+        let (_, line) = spring_line("???.### 1,1,3").expect("valid");
+        assert_eq!(line.arrangements(), vec![spring_line_items("#.#.###")]);
+
+        let (_, line) = spring_line(".??..??...?##. 1,1,3").expect("valid");
+        assert_eq!(
+            line.arrangements(),
+            vec![
+                spring_line_items(".#...#....###."),
+                spring_line_items(".#....#...###."),
+                spring_line_items("..#..#....###."),
+                spring_line_items("..#...#...###."),
+            ]
+        );
+
+        let (_, d) = parse_input(include_str!("../example.txt")).expect("valid");
+        for line in d.lines.iter() {
+            let arrangements = line.arrangements();
+            assert_eq!(arrangements.len() as u64, line.possibilities());
+            for a in arrangements {
+                assert_eq!(a.len(), line.states.len());
+                assert!(!a.contains(&SpringState::Unknown));
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_arrangement() {
+        assert_eq!(render_arrangement(&spring_line_items("#.#.###")), "#.#.###");
+        assert_eq!(render_arrangement(&spring_line_items("?.#??.")), "?.#??.");
+        assert_eq!(render_arrangement(&[]), "");
+
+        let (_, line) = spring_line("???.### 1,1,3").expect("valid");
+        assert_eq!(
+            line.arrangements()
+                .iter()
+                .map(|a| render_arrangement(a))
+                .collect::<Vec<_>>(),
+            vec!["#.#.###"]
+        );
+    }
+
+    #[test]
+    fn test_arrangements_limited() {
+        // 3 separate damaged springs within 20: C(18, 3)
+        let (_, line) = spring_line("???????????????????? 1,1,1").expect("valid");
+        assert_eq!(line.arrangements_limited(100), Err(816));
+        assert_eq!(line.arrangements_limited(816).map(|a| a.len()), Ok(816));
+
+        let (_, line) = spring_line("???.### 1,1,3").expect("valid");
+        assert_eq!(line.arrangements_limited(0), Err(1));
+        assert_eq!(line.arrangements_limited(1).map(|a| a.len()), Ok(1));
+    }
+
     #[test]
     fn test_input() {
         let (r, d) = parse_input(include_str!("../example.txt")).expect("valid");
@@ -7,10 +7,20 @@ fn main() {
 
 #[divan::bench]
 fn part1() {
-    aoc13::part1(black_box(include_str!("../input.txt")));
+    aoc13::part1(black_box(include_str!("../input.txt"))).expect("valid input");
 }
 
 #[divan::bench]
 fn part2() {
-    aoc13::part2(black_box(include_str!("../input.txt")));
+    aoc13::part2(black_box(include_str!("../input.txt"))).expect("valid input");
+}
+
+#[divan::bench]
+fn par_part1() {
+    aoc13::par_part1(black_box(include_str!("../input.txt"))).expect("valid input");
+}
+
+#[divan::bench]
+fn par_part2() {
+    aoc13::par_part2(black_box(include_str!("../input.txt"))).expect("valid input");
 }
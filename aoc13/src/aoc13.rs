@@ -1,4 +1,4 @@
-use aoc13::{part1, part2};
+use aoc13::{part1, part2, PuzzleError};
 
 #[cfg(feature = "dhat-heap")]
 #[global_allocator]
@@ -9,9 +9,13 @@ fn main() {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
 
-    let s1 = part1(include_str!("../input.txt"));
-    println!("Part 1: {}", s1);
+    runner::run(
+        include_str!("../input.txt"),
+        |s| solved(part1(s)),
+        |s| solved(part2(s)),
+    );
+}
 
-    let s2 = part2(include_str!("../input.txt"));
-    println!("Part 2: {}", s2);
+fn solved(result: Result<usize, PuzzleError>) -> usize {
+    result.unwrap_or_else(|(idx, puzzle)| panic!("Puzzle {} has no solution:\n{}", idx, puzzle))
 }
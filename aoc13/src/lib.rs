@@ -10,7 +10,8 @@ use nom::{
     sequence::tuple,
     IResult, Parser,
 };
-use tracing::{info, trace};
+use rayon::prelude::*;
+use tracing::info;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Puzzle {
@@ -47,143 +48,41 @@ impl Display for Puzzle {
     }
 }
 
-fn single_diff(a: ArrayView1<bool>, b: ArrayView1<bool>) -> Option<usize> {
+/// Number of positions where `a` and `b` differ
+fn diff_count(a: ArrayView1<bool>, b: ArrayView1<bool>) -> usize {
     assert_eq!(a.len(), b.len());
 
-    let mut result: Option<usize> = None;
-    for ((idx, va), vb) in a.iter().enumerate().zip(b.iter()) {
-        if *va == *vb {
-            continue;
-        }
-
-        if result.is_none() {
-            result = Some(idx);
-        } else {
-            // two diffs
-            return None;
-        }
-    }
-
-    result
-}
-
-#[derive(Debug, PartialEq, PartialOrd, Copy, Clone)]
-struct ColSmudge {
-    c1: usize,
-    c2: usize,
-    row: usize,
-}
-
-#[derive(Debug, PartialEq, PartialOrd, Copy, Clone)]
-struct RowSmudge {
-    col: usize,
-    r1: usize,
-    r2: usize,
-}
-
-#[derive(Debug, PartialEq, PartialOrd, Copy, Clone)]
-enum Smudge {
-    Col(ColSmudge),
-    Row(RowSmudge),
+    a.iter().zip(b.iter()).filter(|(va, vb)| va != vb).count()
 }
 
 impl Puzzle {
-    fn symmetric_after(&self, pos: usize, axis: Axis) -> bool {
+    /// How many cells differ when mirroring the puzzle after `pos` along `axis`
+    fn mirror_diff_after(&self, pos: usize, axis: Axis) -> usize {
         let (mut left, right) = self.data.view().split_at(axis, pos + 1);
         left.invert_axis(axis);
-        
+
         let other_axis = Axis(1 - axis.0);
-        
+
         left.lanes(other_axis)
             .into_iter()
             .zip(right.lanes(other_axis))
-            .all(|(a, b)| a == b)
-    }
-
-    fn symmetric_after_col(&self, col: usize) -> bool {
-        self.symmetric_after(col, Axis(1))
+            .map(|(a, b)| diff_count(a, b))
+            .sum()
     }
 
-    fn symmetric_after_row(&self, row: usize) -> bool {
-        self.symmetric_after(row, Axis(0))
-    }
-
-    fn flip(&mut self, r: usize, c: usize) {
-        let p = self.data.get_mut((r, c)).expect("valid");
-        *p = !*p;
-    }
+    /// Finds a mirror that has exactly `diffs` differences, checking
+    /// columns first.
+    fn find_mirror_with_diffs(&self, diffs: usize) -> Option<Mirror> {
+        info!("CHECKING for {} diffs:\n{}\n\n", diffs, self);
 
-    fn fix_smudge(&mut self) -> Option<Mirror> {
-        // find two lines that seem to be the same and fixing them
-        // results in a different symmetry
-        info!("CHECKING SMUDGE IN:\n{}\n\n", self);
-
-        let mut smudge_options = Vec::new();
-
-        for r1 in 0..(self.data.nrows() - 1) {
-            for r2 in (r1 + 1)..self.data.nrows() {
-                let col = single_diff(self.data.row(r1), self.data.row(r2));
-                if let Some(col) = col {
-                    trace!("  MAYBE DIFF BY 1 in rows: {},{}", r1, r2);
-                    smudge_options.push(Smudge::Row(RowSmudge { r1, r2, col }));
-                }
-            }
-        }
-
-        for c1 in 0..(self.data.ncols() - 1) {
-            for c2 in (c1 + 1)..self.data.ncols() {
-                let row = single_diff(self.data.column(c1), self.data.column(c2));
-                if let Some(row) = row {
-                    trace!("  MAYBE DIFF BY 1 in columns: {},{}", c1, c2);
-                    smudge_options.push(Smudge::Col(ColSmudge { c1, c2, row }));
-                }
-            }
-        }
-        info!("Potential smudges: {:?}", smudge_options);
-
-        for option in smudge_options {
-            match option {
-                Smudge::Col(c) => {
-                    // any row should be ok to flip, pick one
-                    self.flip(c.row, c.c1);
-
-                    let symmetry_point = c.c1 + (c.c2 - c.c1) / 2;
-                    if self.symmetric_after_col(symmetry_point) {
-                        return Some(Mirror::AfterCol(symmetry_point));
-                    }
-
-                    // undo the flip if failed
-                    self.flip(c.row, c.c1);
-                }
-                Smudge::Row(r) => {
-                    // any col should be ok to flip, pick one
-                    self.flip(r.r1, r.col);
-
-                    let symmetry_point = r.r1 + (r.r2 - r.r1) / 2;
-                    if self.symmetric_after_row(symmetry_point) {
-                        return Some(Mirror::AfterRow(symmetry_point));
-                    }
-
-                    // undo the flip if failed
-                    self.flip(r.r1, r.col);
-                }
-            }
-        }
-
-        None
-    }
-
-    fn find_symmetry(&self) -> Option<Mirror> {
-        info!("CHECKING:\n{}\n\n", self);
-        // find which row or column is symmetric
         for col in 0..(self.data.ncols() - 1) {
-            if self.symmetric_after_col(col) {
+            if self.mirror_diff_after(col, Axis(1)) == diffs {
                 return Some(Mirror::AfterCol(col));
             }
         }
 
         for row in 0..(self.data.nrows() - 1) {
-            if self.symmetric_after_row(row) {
+            if self.mirror_diff_after(row, Axis(0)) == diffs {
                 return Some(Mirror::AfterRow(row));
             }
         }
@@ -191,11 +90,17 @@ impl Puzzle {
         None
     }
 
-    fn score_symmetry(&self) -> usize {
-        match self.find_symmetry() {
-            Some(m) => m.score(),
-            None => panic!("no symmetry found for {}", self),
-        }
+    fn find_symmetry(&self) -> Option<Mirror> {
+        self.find_mirror_with_diffs(0)
+    }
+
+    /// Finds the symmetry that would exist once exactly one smudge is fixed
+    fn find_smudged_symmetry(&self) -> Option<Mirror> {
+        self.find_mirror_with_diffs(1)
+    }
+
+    fn score_symmetry(&self) -> Option<usize> {
+        self.find_symmetry().map(|m| m.score())
     }
 }
 
@@ -235,19 +140,60 @@ fn parse_input(input: &str) -> Input {
     data
 }
 
-pub fn part1(input: &str) -> usize {
+/// Index and rendered content of a puzzle that could not be solved
+pub type PuzzleError = (usize, String);
+
+fn part1_score(idx: usize, puzzle: &Puzzle) -> Result<usize, PuzzleError> {
+    puzzle
+        .score_symmetry()
+        .ok_or_else(|| (idx, puzzle.to_string()))
+}
+
+fn part2_score(idx: usize, puzzle: &Puzzle) -> Result<usize, PuzzleError> {
+    puzzle
+        .find_smudged_symmetry()
+        .map(|m| m.score())
+        .ok_or_else(|| (idx, puzzle.to_string()))
+}
+
+pub fn part1(input: &str) -> Result<usize, PuzzleError> {
+    parse_input(input)
+        .puzzles
+        .iter()
+        .enumerate()
+        .map(|(idx, d)| part1_score(idx, d))
+        .sum()
+}
+
+pub fn part2(input: &str) -> Result<usize, PuzzleError> {
     parse_input(input)
         .puzzles
         .iter()
-        .map(|d| d.score_symmetry())
+        .enumerate()
+        .map(|(idx, d)| part2_score(idx, d))
         .sum()
 }
 
-pub fn part2(input: &str) -> usize {
+pub fn par_part1(input: &str) -> Result<usize, PuzzleError> {
+    // collect first so that the reported error is always the first failing puzzle
     parse_input(input)
         .puzzles
+        .par_iter()
+        .enumerate()
+        .map(|(idx, d)| part1_score(idx, d))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .sum()
+}
+
+pub fn par_part2(input: &str) -> Result<usize, PuzzleError> {
+    parse_input(input)
+        .puzzles
+        .par_iter()
+        .enumerate()
+        .map(|(idx, d)| part2_score(idx, d))
+        .collect::<Vec<_>>()
         .into_iter()
-        .map(|d| d.clone().fix_smudge().expect("has smudge").score())
         .sum()
 }
 
@@ -266,12 +212,59 @@ mod tests {
 
     #[test_log::test]
     fn test_part1() {
-        assert_eq!(part1(include_str!("../example.txt")), 405);
+        assert_eq!(part1(include_str!("../example.txt")), Ok(405));
     }
 
     #[test_log::test]
     fn test_part2() {
-        assert_eq!(part2(include_str!("../example.txt")), 400);
+        assert_eq!(part2(include_str!("../example.txt")), Ok(400));
+    }
+
+    #[test]
+    fn test_unsolvable_puzzle() {
+        let input = "#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#..
+.#.
+..#";
+
+        assert_eq!(part1(input), Err((1, "#..\n.#.\n..#\n".into())));
+        assert_eq!(part2(input), Err((1, "#..\n.#.\n..#\n".into())));
+    }
+
+    #[test]
+    fn test_parallel() {
+        assert_eq!(par_part1(include_str!("../example.txt")), Ok(405));
+        assert_eq!(par_part2(include_str!("../example.txt")), Ok(400));
+        assert_eq!(
+            par_part1(include_str!("../input.txt")),
+            part1(include_str!("../input.txt"))
+        );
+        assert_eq!(
+            par_part2(include_str!("../input.txt")),
+            part2(include_str!("../input.txt"))
+        );
+    }
+
+    #[test]
+    fn test_diff_count() {
+        let a = array![true, false, true, true, false];
+
+        assert_eq!(diff_count(a.view(), a.view()), 0);
+        assert_eq!(
+            diff_count(a.view(), array![true, false, false, true, false].view()),
+            1
+        );
+        assert_eq!(
+            diff_count(a.view(), array![false, true, true, true, true].view()),
+            3
+        );
     }
 
     #[test_log::test]
@@ -288,7 +281,7 @@ mod tests {
             )
             .expect("valid input")
             .1
-            .fix_smudge(),
+            .find_smudged_symmetry(),
             Some(Mirror::AfterRow(0))
         );
     }
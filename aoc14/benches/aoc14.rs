@@ -7,10 +7,10 @@ fn main() {
 
 #[divan::bench]
 fn part1() {
-    aoc14::part1(black_box(include_str!("../input.txt")));
+    aoc14::part1(black_box(include_str!("../input.txt"))).expect("valid input");
 }
 
 #[divan::bench]
 fn part2() {
-    aoc14::part2(black_box(include_str!("../input.txt")), 1000000000);
+    aoc14::part2(black_box(include_str!("../input.txt")), 1000000000).expect("valid input");
 }
@@ -9,9 +9,9 @@ fn main() {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
 
-    let s1 = part1(include_str!("../input.txt"));
-    println!("Part 1: {}", s1);
-
-    let s2 = part2(include_str!("../input.txt"), 1000000000);
-    println!("Part 2: {}", s2);
+    runner::run(
+        include_str!("../input.txt"),
+        |s| part1(s).expect("valid input"),
+        |s| part2(s, 1000000000).expect("valid input"),
+    );
 }
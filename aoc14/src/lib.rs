@@ -1,8 +1,18 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap},
     fmt::{Display, Write},
 };
 
+use grid::Direction;
+
+/// Standard spin cycle: north, west, south, east
+const SPIN_CYCLE: [Direction; 4] = [
+    Direction::Up,
+    Direction::Left,
+    Direction::Down,
+    Direction::Right,
+];
+
 #[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Hash, Copy, Clone)]
 enum Item {
     Free,
@@ -10,13 +20,15 @@ enum Item {
     Immovable,
 }
 
-impl From<char> for Item {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Item {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '.' => Item::Free,
-            'O' => Item::Movable,
-            '#' => Item::Immovable,
-            _ => unreachable!(),
+            '.' => Ok(Item::Free),
+            'O' => Ok(Item::Movable),
+            '#' => Ok(Item::Immovable),
+            _ => Err(format!("Invalid map item: {:?}", value)),
         }
     }
 }
@@ -67,10 +79,11 @@ impl Map {
     }
 
     fn cols(&self) -> usize {
-        self.data.get(0).map(|v| v.len()).unwrap_or(0)
+        self.data.first().map(|v| v.len()).unwrap_or(0)
     }
 
-    fn move_pos(&self, pos: (usize, usize), dir: (i32, i32)) -> Option<(usize, usize)> {
+    fn move_pos(&self, pos: (usize, usize), dir: Direction) -> Option<(usize, usize)> {
+        let dir = dir.delta();
         let test_r = pos.0 as i32 + dir.0;
         if test_r < 0 || test_r >= self.rows() as i32 {
             return None;
@@ -84,16 +97,17 @@ impl Map {
         Some((test_r as usize, test_c as usize))
     }
 
-    fn push(&mut self, dir: (i32, i32)) {
+    fn push(&mut self, dir: Direction) {
         // Somewhat slow algorithm to push one space up each time
-        let row_range: Vec<usize> = match dir.0 {
+        let delta = dir.delta();
+        let row_range: Vec<usize> = match delta.0 {
             -1 => (1..self.rows()).collect(),
             0 => (0..self.rows()).collect(),
             1 => (0..(self.rows() - 1)).rev().collect(),
             _ => unreachable!(),
         };
 
-        let col_range: Vec<usize> = match dir.1 {
+        let col_range: Vec<usize> = match delta.1 {
             -1 => (1..self.cols()).collect(),
             0 => (0..self.cols()).collect(),
             1 => (0..(self.cols() - 1)).rev().collect(),
@@ -102,7 +116,7 @@ impl Map {
 
         for r in row_range {
             for c in col_range.as_slice() {
-                let mut current = (r as usize, *c as usize);
+                let mut current = (r, *c);
                 let mut other = self.move_pos(current, dir).expect("valid");
                 if self.at(current) != Item::Movable {
                     continue;
@@ -131,85 +145,116 @@ impl Map {
     }
 
     fn push_up(&mut self) {
-        self.push((-1, 0));
+        self.push(Direction::Up);
     }
 
-    fn cycle(&mut self) {
-        self.push((-1, 0));
-        self.push((0, -1));
-        self.push((1, 0));
-        self.push((0, 1));
+    fn push_down(&mut self) {
+        self.push(Direction::Down);
     }
 
-    fn score_weight(&self) -> usize {
-        let mut total = 0usize;
+    fn push_left(&mut self) {
+        self.push(Direction::Left);
+    }
 
-        for r in 0..self.rows() {
-            for c in 0..self.rows() {
-                if self.at((r, c)) == Item::Movable {
-                    total += self.rows() - r;
-                }
+    fn push_right(&mut self) {
+        self.push(Direction::Right);
+    }
+
+    /// Tilts the map in each of the given directions, in order
+    fn spin(&mut self, dirs: &[Direction]) {
+        for dir in dirs {
+            match dir {
+                Direction::Up => self.push_up(),
+                Direction::Down => self.push_down(),
+                Direction::Left => self.push_left(),
+                Direction::Right => self.push_right(),
             }
         }
-
-        total
     }
-}
 
-fn parse_map(input: &str) -> Map {
-    Map {
-        data: input
-            .split('\n')
-            .map(|line| line.chars().map(|c| c.into()).collect())
-            .collect(),
+    /// Positions (row, col) of all the movable rocks
+    fn movable_positions(&self) -> BTreeSet<(usize, usize)> {
+        (0..self.rows())
+            .flat_map(|r| (0..self.cols()).map(move |c| (r, c)))
+            .filter(|pos| self.at(*pos) == Item::Movable)
+            .collect()
     }
-}
 
-pub fn part1(input: &str) -> usize {
-    let mut map = parse_map(input);
-    map.push_up();
-    map.score_weight()
+    fn score_weight(&self) -> usize {
+        self.movable_positions()
+            .iter()
+            .map(|(r, _)| self.rows() - r)
+            .sum()
+    }
 }
 
-pub fn part2(input: &str, cnt: usize) -> usize {
-    let mut map = parse_map(input);
+/// Parses a rectangular map. A trailing newline is allowed.
+fn parse_map(input: &str) -> Result<Map, String> {
+    let input = input.strip_suffix('\n').unwrap_or(input);
+    if input.is_empty() {
+        return Err("Empty map".into());
+    }
 
-    let dirs = vec![(-1, 0), (0, -1), (1, 0), (0, 1)];
+    let data = input
+        .split('\n')
+        .map(|line| line.chars().map(Item::try_from).collect())
+        .collect::<Result<Vec<Vec<_>>, _>>()?;
+
+    let cols = data[0].len();
+    if let Some((row, line)) = data.iter().enumerate().find(|(_, l)| l.len() != cols) {
+        return Err(format!(
+            "Row {} has {} columns, expected {}",
+            row,
+            line.len(),
+            cols
+        ));
+    }
 
-    // do one cycle to start in a maybe-stable position
-    let mut rotations = 0;
-    let mut options = HashSet::new();
+    Ok(Map { data })
+}
 
-    while rotations < cnt {
-        map.cycle();
-        rotations += 1;
+pub fn part1(input: &str) -> Result<usize, String> {
+    let mut map = parse_map(input)?;
+    map.push_up();
+    Ok(map.score_weight())
+}
 
-        if options.contains(&map) {
-            break;
+/// Spins the map `cnt` times using the tilt directions in `dirs`.
+///
+/// Maps eventually repeat, so spins are only simulated until the first
+/// repeated state. Returns the load after `cnt` spins and the length of
+/// the spin cycle that the map ends up in.
+fn find_cycle_for(input: &str, dirs: &[Direction], cnt: usize) -> Result<(usize, usize), String> {
+    let mut map = parse_map(input)?;
+
+    // seen[map] is the spin count after which `map` was first seen
+    let mut seen = HashMap::new();
+    let mut loads = Vec::new();
+
+    let mut spins = 0;
+    let start = loop {
+        if let Some(start) = seen.get(&map) {
+            break *start;
         }
-        options.insert(map.clone());
-    }
+        loads.push(map.score_weight());
+        seen.insert(map.clone(), spins);
 
-    let target = map.clone();
-    let mut cycle_size = 0usize;
-    loop {
-        map.cycle();
-        cycle_size += 1;
-        rotations += 1;
-        if map == target {
-            break;
-        }
-    }
+        map.spin(dirs);
+        spins += 1;
+    };
 
-    let left = cnt - rotations;
-    let left = left % cycle_size;
-    for _ in 0..left {
-        for dir in dirs.iter() {
-            map.push(*dir);
-        }
-    }
+    let period = spins - start;
+    let load = if cnt < spins {
+        loads[cnt]
+    } else {
+        loads[start + (cnt - start) % period]
+    };
+
+    Ok((load, period))
+}
 
-    map.score_weight()
+pub fn part2(input: &str, cnt: usize) -> Result<usize, String> {
+    find_cycle_for(input, &SPIN_CYCLE, cnt).map(|(load, _)| load)
 }
 
 #[cfg(test)]
@@ -218,25 +263,117 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(include_str!("../example.txt")), 136);
+        assert_eq!(part1(include_str!("../example.txt")), Ok(136));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(include_str!("../example.txt"), 1000000000), 64);
+        assert_eq!(part2(include_str!("../example.txt"), 1000000000), Ok(64));
+    }
+
+    #[test]
+    fn test_find_cycle_for() {
+        let example = include_str!("../example.txt");
+        assert_eq!(
+            find_cycle_for(example, &SPIN_CYCLE, 1000000000),
+            Ok((64, 7))
+        );
+
+        let reversed = SPIN_CYCLE.iter().rev().copied().collect::<Vec<_>>();
+        let (_, period) = find_cycle_for(example, &reversed, 1000000000).expect("valid");
+        assert!(period > 0);
+
+        // period is valid: once in the cycle, spinning `period` times is a no-op
+        let mut map = parse_map(example).expect("valid");
+        for _ in 0..100 {
+            map.spin(&reversed);
+        }
+        let target = map.clone();
+        for _ in 0..period {
+            map.spin(&reversed);
+        }
+        assert_eq!(map, target);
+
+        // loads match an actual simulation, both before and after the cycle start
+        let mut map = parse_map(example).expect("valid");
+        for cnt in 0..50 {
+            assert_eq!(
+                find_cycle_for(example, &reversed, cnt),
+                Ok((map.score_weight(), period))
+            );
+            map.spin(&reversed);
+        }
+    }
+
+    #[test]
+    fn test_trailing_newline() {
+        let input = format!("{}\n", include_str!("../example.txt"));
+
+        assert_eq!(part1(&input), Ok(136));
+        assert_eq!(part2(&input, 1000000000), Ok(64));
+    }
+
+    #[test]
+    fn test_invalid_maps() {
+        assert_eq!(
+            parse_map("#.O\nOO\n..#"),
+            Err("Row 1 has 2 columns, expected 3".into())
+        );
+        assert_eq!(
+            part1("#.O\nOOO\n..#.\n"),
+            Err("Row 2 has 4 columns, expected 3".into())
+        );
+        assert_eq!(part1(""), Err("Empty map".into()));
+        assert_eq!(part1("\n"), Err("Empty map".into()));
+        assert!(part1("#.O\nOxO").is_err());
+
+        // non-square maps and nothing to move are fine
+        assert_eq!(part1("O...\n.#.O"), Ok(4));
+        assert_eq!(part1("#..\n.#.\n..#"), Ok(0));
+        assert_eq!(part2("#..\n.#.\n..#", 1000), Ok(0));
     }
 
     #[test]
     fn test_push_example() {
-        let mut map = parse_map(include_str!("../example.txt"));
+        let mut map = parse_map(include_str!("../example.txt")).expect("valid");
         map.push_up();
 
-        assert_eq!(map, parse_map(include_str!("../example_pushed.txt")));
+        assert_eq!(
+            map,
+            parse_map(include_str!("../example_pushed.txt")).expect("valid")
+        );
+    }
+
+    #[test]
+    fn test_movable_positions() {
+        let mut map = parse_map(include_str!("../example.txt")).expect("valid");
+        let before = map.movable_positions();
+        map.push_up();
+
+        let pushed = parse_map(include_str!("../example_pushed.txt")).expect("valid");
+        assert_eq!(map.movable_positions(), pushed.movable_positions());
+        assert_eq!(map.movable_positions().len(), before.len());
+
+        // first column packs its 4 rocks at the top
+        assert_eq!(
+            map.movable_positions()
+                .into_iter()
+                .filter(|(_, c)| *c == 0)
+                .collect::<Vec<_>>(),
+            vec![(0, 0), (1, 0), (2, 0), (3, 0)]
+        );
+
+        let mut map = parse_map("#.O\n...\nOOO").expect("valid");
+        map.push_up();
+        assert_eq!(
+            map.movable_positions(),
+            BTreeSet::from([(0, 1), (0, 2), (1, 0), (1, 2)])
+        );
     }
 
     #[test]
     fn test_push_up() {
-        let mut map = parse_map("#.O\n...\nOOO");
+        let mut map = parse_map("#.O\n...\nOOO").expect("valid");
 
         map.push_up();
 
@@ -252,9 +389,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_push_left() {
+        let mut map = parse_map(".O.O\n#..O\n..#O\nOOO.").expect("valid");
+
+        map.push_left();
+
+        assert_eq!(map, parse_map("OO..\n#O..\n..#O\nOOO.").expect("valid"));
+    }
+
+    #[test]
+    fn test_push_directions() {
+        let mut map = parse_map(".O.\n.#.\nO..").expect("valid");
+
+        map.push_right();
+        assert_eq!(map, parse_map("..O\n.#.\n..O").expect("valid"));
+
+        map.push_down();
+        assert_eq!(map, parse_map("...\n.#O\n..O").expect("valid"));
+
+        map.spin(&SPIN_CYCLE);
+        assert_eq!(map, parse_map("...\n.#.\n.OO").expect("valid"));
+    }
+
     #[test]
     fn test_swap() {
-        let mut map = parse_map("#.O\nOOO\n..#");
+        let mut map = parse_map("#.O\nOOO\n..#").expect("valid");
         map.swap((0, 0), (2, 1));
 
         assert_eq!(
@@ -273,13 +433,13 @@ mod tests {
     fn test_map_parse() {
         assert_eq!(
             parse_map("#.O\nOOO\n..#"),
-            Map {
+            Ok(Map {
                 data: vec![
                     vec![Item::Immovable, Item::Free, Item::Movable],
                     vec![Item::Movable, Item::Movable, Item::Movable],
                     vec![Item::Free, Item::Free, Item::Immovable],
                 ],
-            }
+            })
         );
     }
 }
@@ -18,10 +18,18 @@ fn hash_string(s: &str) -> u8 {
     s.chars().fold(0, update_hash)
 }
 
+/// Splits the initialization sequence into steps.
+///
+/// Steps are comma or newline separated, empty steps (e.g. from a trailing
+/// newline) are skipped.
+fn steps(s: &str) -> impl Iterator<Item = &str> {
+    s.split(['\n', ','])
+        .map(|step| step.trim())
+        .filter(|step| !step.is_empty())
+}
+
 pub fn part1(s: &str) -> usize {
-    s.split('\n')
-        .flat_map(|l| l.split(','))
-        .fold(0, |acc, s| acc + hash_string(s) as usize)
+    steps(s).fold(0, |acc, s| acc + hash_string(s) as usize)
 }
 
 #[derive(Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
@@ -107,34 +115,50 @@ impl<'a> Mapping<'a> {
         }
     }
 
-    fn total_focusing_power(&self) -> usize {
-        let mut total = 0;
+    /// Focusing power of every non-empty box, as (box index, power)
+    fn focusing_power_by_box(&self) -> Vec<(usize, usize)> {
+        let mut result = Vec::new();
         for idx in 0..256 {
             let key = idx as u8;
-            if let Some(v) = self.map.get(&key) {
-                let multiplier = (idx + 1) as usize;
-                total += multiplier
-                    * v.iter()
-                        .enumerate()
-                        .fold(0, |acc, (idx, v)| acc + ((idx + 1) * v.focus as usize))
+            match self.map.get(&key) {
+                Some(v) if !v.is_empty() => {
+                    let multiplier = idx + 1;
+                    result.push((
+                        idx,
+                        multiplier
+                            * v.iter()
+                                .enumerate()
+                                .fold(0, |acc, (idx, v)| acc + ((idx + 1) * v.focus as usize)),
+                    ));
+                }
+                _ => {}
             }
         }
-        total
+        result
+    }
+
+    fn total_focusing_power(&self) -> usize {
+        self.focusing_power_by_box().iter().map(|(_, p)| p).sum()
     }
 }
 
-pub fn part2(s: &str) -> usize {
+fn initialize(s: &str) -> Mapping<'_> {
     let mut m = Mapping::new();
-    for action in s
-        .split('\n')
-        .flat_map(|l| l.split(','))
-        .map(|s| s.into())
-    {
+    for action in steps(s).map(|s| s.into()) {
         m.perform(&action);
         info!("After {:?}: {:?}", &action, &m);
     }
+    m
+}
+
+pub fn part2(s: &str) -> usize {
+    initialize(s).total_focusing_power()
+}
 
-    m.total_focusing_power()
+/// Focusing power of every non-empty box, as (box index, power), after
+/// running all the steps. Powers sum up to [part2].
+pub fn focusing_power_by_box(s: &str) -> Vec<(usize, usize)> {
+    initialize(s).focusing_power_by_box()
 }
 
 #[cfg(test)]
@@ -148,6 +172,21 @@ mod tests {
             145
         );
     }
+
+    #[test_log::test]
+    fn test_focusing_power_by_box() {
+        let input = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+        assert_eq!(focusing_power_by_box(input), vec![(0, 5), (3, 140)]);
+        assert_eq!(
+            focusing_power_by_box(input)
+                .iter()
+                .map(|(_, p)| p)
+                .sum::<usize>(),
+            part2(input)
+        );
+    }
+
     #[test]
     fn test_into_action() {
         assert_eq!(
@@ -195,6 +234,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_trailing_newline() {
+        let input = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+        let trailing = format!("{}\n", input);
+        let multiline = "rn=1,cm-,qp=3,cm=2,\nqp-,pc=4,ot=9,ab=5\npc-,pc=6,ot=7\n";
+
+        assert_eq!(part1(&trailing), part1(input));
+        assert_eq!(part1(multiline), part1(input));
+        assert_eq!(part2(&trailing), part2(input));
+        assert_eq!(part2(multiline), part2(input));
+    }
+
     #[test_log::test]
     fn test_hash() {
         assert_eq!(update_hash(0, 'H'), 200);
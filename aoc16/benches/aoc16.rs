@@ -14,3 +14,8 @@ fn part1() {
 fn part2() {
     aoc16::part2(black_box(include_str!("../input.txt")));
 }
+
+#[divan::bench]
+fn solve() {
+    aoc16::solve(black_box(include_str!("../input.txt").as_bytes())).expect("valid input");
+}
@@ -1,10 +1,13 @@
 use std::{
     collections::{HashMap, VecDeque},
     fmt::Write,
+    io::{self, BufRead},
+    sync::Arc,
 };
 
 use rayon::prelude::*;
 
+use grid::{Direction, Grid};
 use itertools::Itertools;
 use nom::{
     branch::alt,
@@ -46,14 +49,6 @@ impl std::fmt::Display for Tile {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
-enum Direction {
-    Left,
-    Right,
-    Up,
-    Down,
-}
-
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Default)]
 struct Beam {
     up: bool,
@@ -118,25 +113,23 @@ impl Beam {
 
 #[derive(Clone)]
 struct LightMap {
-    map: HashMap<(usize, usize), Tile>,
+    map: Arc<Grid<Option<Tile>>>,
     energy: HashMap<(usize, usize), Beam>,
-    rows: usize,
-    cols: usize,
 }
 
 impl std::fmt::Display for LightMap {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in 0..self.rows {
-            for col in 0..self.cols {
-                match self.map.get(&(row, col)) {
-                    Some(t) => f.write_fmt(format_args!("{}", t))?,
-                    None => f.write_char('.')?,
+        for row in 0..self.map.rows() {
+            for col in 0..self.map.cols() {
+                match self.map.get((row, col)) {
+                    Some(Some(t)) => f.write_fmt(format_args!("{}", t))?,
+                    _ => f.write_char('.')?,
                 }
             }
 
             f.write_str("    |    ")?;
 
-            for col in 0..self.cols {
+            for col in 0..self.map.cols() {
                 f.write_char(match self.energy.get(&(row, col)) {
                     Some(b) => b.display_char(),
                     None => '.',
@@ -150,25 +143,34 @@ impl std::fmt::Display for LightMap {
 }
 
 impl LightMap {
-    fn new(mirror_map: &Vec<(usize, usize, Tile)>, rows: usize, cols: usize) -> Self {
+    fn new(mirror_map: &[(usize, usize, Tile)], rows: usize, cols: usize) -> Self {
+        let mut map = Grid::filled(rows, cols, None);
+        for (r, c, t) in mirror_map {
+            *map.get_mut((*r, *c)).expect("mirror inside the map") = Some(*t);
+        }
+
         Self {
             energy: HashMap::new(),
-            map: mirror_map.iter().map(|(r, c, t)| ((*r, *c), *t)).collect(),
-            rows,
-            cols,
+            map: Arc::new(map),
         }
     }
 
-    fn move_towards(&self, row: usize, col: usize, d: Direction) -> Option<(usize, usize)> {
-        match d {
-            Direction::Up if row > 0 => Some((row - 1, col)),
-            Direction::Down if row + 1 < self.rows => Some((row + 1, col)),
-            Direction::Left if col > 0 => Some((row, col - 1)),
-            Direction::Right if col + 1 < self.cols => Some((row, col + 1)),
-            _ => None,
+    /// A map sharing the same tiles, without any energy
+    fn without_energy(&self) -> Self {
+        Self {
+            map: Arc::clone(&self.map),
+            energy: HashMap::new(),
         }
     }
 
+    fn reset_energy(&mut self) {
+        self.energy.clear();
+    }
+
+    fn move_towards(&self, row: usize, col: usize, d: Direction) -> Option<(usize, usize)> {
+        self.map.step((row, col), d)
+    }
+
     /// Beams the light at the specified row, column and direction
     /// returns where the light goes from there
     fn beam_step(
@@ -177,7 +179,7 @@ impl LightMap {
         col: usize,
         d: Direction,
     ) -> Vec<(usize, usize, Direction)> {
-        let map_element = self.map.get(&(row, col));
+        let map_element = self.map.get((row, col)).copied().flatten();
 
         // Energize current tile
         match self.energy.get_mut(&(row, col)) {
@@ -274,34 +276,74 @@ impl LightMap {
         }
     }
 
-    // Runs energy calculation but resets enegy map back
+    // Runs energy calculation on a fresh energy map, leaving this one unchanged
     fn energy_for_beam(&self, row: usize, col: usize, d: Direction) -> usize {
-        let mut copy = self.clone();
-        copy.energy.clear();
+        let mut copy = self.without_energy();
         copy.send_light(row, col, d);
-        let energy = copy.count_energy();
-        energy
+        copy.count_energy()
     }
 
-    // RETURNS: row, col, energy
-    fn max_energy(&mut self) -> (usize, usize, Direction, usize) {
-        (0..self.rows)
+    // RETURNS: row, col, direction, energy for every start on all four edges
+    fn energy_by_start(&self) -> Vec<(usize, usize, Direction, usize)> {
+        let (rows, cols) = (self.map.rows(), self.map.cols());
+
+        (0..rows)
             .map(|r| (r, 0, Direction::Right))
-            .chain((0..self.cols).map(|c| (0, c, Direction::Down)))
+            .chain((0..rows).map(|r| (r, cols - 1, Direction::Left)))
+            .chain((0..cols).map(|c| (0, c, Direction::Down)))
+            .chain((0..cols).map(|c| (rows - 1, c, Direction::Up)))
             .collect_vec()
             .par_iter()
-            .map(|(r, c, d)| (*r, *c, *d, self.energy_for_beam(*r, *c, *d)))
+            .map_init(
+                // energy maps are reused within a thread
+                || self.without_energy(),
+                |m, (r, c, d)| {
+                    m.reset_energy();
+                    m.send_light(*r, *c, *d);
+                    (*r, *c, *d, m.count_energy())
+                },
+            )
+            .collect()
+    }
+
+    // RETURNS: row, col, direction, energy
+    fn max_energy(&self) -> (usize, usize, Direction, usize) {
+        self.energy_by_start()
+            .into_iter()
             .max_by(|a, b| a.3.cmp(&b.3))
             .expect("Has value")
-
     }
 
     fn count_energy(&self) -> usize {
         self.energy.iter().filter(|(_, b)| b.is_energized()).count()
     }
+
+    /// How many tiles carry a beam going up, down, left and right, in that
+    /// order, followed by the total number of energized tiles.
+    ///
+    /// A tile crossed by several beams is counted once per direction.
+    fn energy_breakdown(&self) -> (u64, u64, u64, u64, u64) {
+        let count = |d: Direction| {
+            self.energy
+                .values()
+                .filter(|b| b.is_energized_in_direction(d))
+                .count() as u64
+        };
+
+        (
+            count(Direction::Up),
+            count(Direction::Down),
+            count(Direction::Left),
+            count(Direction::Right),
+            self.count_energy() as u64,
+        )
+    }
 }
 
-fn input_row(input: LocatedSpan<&str>) -> IResult<LocatedSpan<&str>, (usize, Vec<(usize, Tile)>)> {
+/// A parsed row: its length and the tiles at each column
+type InputRow = (usize, Vec<(usize, Tile)>);
+
+fn input_row(input: LocatedSpan<&str>) -> IResult<LocatedSpan<&str>, InputRow> {
     many1(alt((
         value(Some(Tile::Split(SplitDirection::UpDown)), tag("|")),
         value(Some(Tile::Split(SplitDirection::LeftRight)), tag("-")),
@@ -346,21 +388,100 @@ fn parse_input(input: LocatedSpan<&str>) -> (usize, usize, Vec<(usize, usize, Ti
         .1
 }
 
-pub fn part1(input: &str) -> usize {
+/// Energized tile count for a beam entering at `row`/`col`.
+///
+/// `dir_char` is the beam direction, one of `^`, `v`, `<` or `>`.
+///
+/// Panics on an unknown direction.
+pub fn energy_from(input: &str, row: usize, col: usize, dir_char: char) -> usize {
+    let map = beam_map(input, row, col, dir_char);
+    info!(
+        "Energy breakdown (up, down, left, right, total): {:?}",
+        map.energy_breakdown()
+    );
+    map.count_energy()
+}
+
+/// The mirror map next to the beam arrows, for a beam entering at
+/// `row`/`col` going towards `dir_char` (same directions as [energy_from]).
+pub fn render_beams(input: &str, row: usize, col: usize, dir_char: char) -> String {
+    beam_map(input, row, col, dir_char).to_string()
+}
+
+fn direction_from_char(dir_char: char) -> Direction {
+    match dir_char {
+        '^' => Direction::Up,
+        'v' => Direction::Down,
+        '<' => Direction::Left,
+        '>' => Direction::Right,
+        _ => panic!("Invalid direction: {:?}", dir_char),
+    }
+}
+
+fn direction_char(d: Direction) -> char {
+    match d {
+        Direction::Up => '^',
+        Direction::Down => 'v',
+        Direction::Left => '<',
+        Direction::Right => '>',
+    }
+}
+
+fn beam_map(input: &str, row: usize, col: usize, dir_char: char) -> LightMap {
+    let d = direction_from_char(dir_char);
+
     let (rows, cols, m) = parse_input(input.into());
     let mut map = LightMap::new(&m, rows, cols);
     info!("BEFORE:\n{}", &map);
-    map.send_light(0, 0, Direction::Right);
+    map.send_light(row, col, d);
     info!("AFTER:\n{}", &map);
-    map.count_energy()
+    map
+}
+
+pub fn part1(input: &str) -> usize {
+    energy_from(input, 0, 0, '>')
 }
 
 pub fn part2(input: &str) -> usize {
     let (rows, cols, m) = parse_input(input.into());
-    let mut map = LightMap::new(&m, rows, cols);
+    let map = LightMap::new(&m, rows, cols);
     map.max_energy().3
 }
 
+/// Energized tile count for every entry on the edges of the map, as
+/// `(row, col, direction, energy)` using the same directions as [energy_from].
+pub fn energy_by_start(input: &str) -> Vec<(usize, usize, char, usize)> {
+    let (rows, cols, m) = parse_input(input.into());
+    LightMap::new(&m, rows, cols)
+        .energy_by_start()
+        .into_iter()
+        .map(|(row, col, d, energy)| (row, col, direction_char(d), energy))
+        .collect()
+}
+
+/// The edge entry with the most energized tiles, as `(row, col, direction,
+/// energy)` using the same directions as [energy_from].
+pub fn best_start(input: &str) -> (usize, usize, char, usize) {
+    let (rows, cols, m) = parse_input(input.into());
+    let (row, col, d, energy) = LightMap::new(&m, rows, cols).max_energy();
+    (row, col, direction_char(d), energy)
+}
+
+/// Reads the input and computes both the [part1] and [part2] answers,
+/// parsing the map only once.
+pub fn solve<R: BufRead>(mut r: R) -> io::Result<(usize, usize)> {
+    let mut buffer = String::new();
+    r.read_to_string(&mut buffer)?;
+
+    let (rows, cols, m) = parse_input(buffer.trim_end().into());
+    let map = LightMap::new(&m, rows, cols);
+
+    Ok((
+        map.energy_for_beam(0, 0, Direction::Right),
+        map.max_energy().3,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -370,6 +491,102 @@ mod tests {
         assert_eq!(part1(include_str!("../example.txt")), 46);
     }
 
+    #[test_log::test]
+    fn test_energy_from() {
+        let example = include_str!("../example.txt");
+        assert_eq!(energy_from(example, 0, 0, '>'), 46);
+        assert_eq!(energy_from(example, 0, 3, 'v'), 51);
+
+        // bottom-left going up only passes through the first column
+        assert_eq!(energy_from(example, 9, 0, '^'), 10);
+    }
+
+    #[test_log::test]
+    fn test_render_beams() {
+        let rendered = render_beams("..\\\n...\n-./", 0, 0, '>');
+        assert_eq!(
+            rendered,
+            "
+..\\    |    →→→
+...    |    ..↓
+-./    |    ←←↓
+"
+            .trim_start()
+        );
+    }
+
+    #[test_log::test]
+    fn test_best_start() {
+        let example = include_str!("../example.txt");
+
+        let (row, col, dir, energy) = best_start(example);
+        assert_eq!(energy, 51);
+        assert_eq!((row, col, dir), (0, 3, 'v'));
+        assert_eq!(energy_from(example, row, col, dir), energy);
+        assert_eq!(energy, part2(example));
+    }
+
+    #[test_log::test]
+    fn test_energy_by_start() {
+        let example = include_str!("../example.txt");
+        let energies = energy_by_start(example);
+
+        assert_eq!(energies.len(), 2 * 10 + 2 * 10);
+        assert_eq!(energies.iter().map(|e| e.3).max(), Some(51));
+        assert!(energies.contains(&(0, 3, 'v', 51)));
+
+        for (row, col, dir, energy) in energies {
+            assert_eq!(energy_from(example, row, col, dir), energy);
+        }
+    }
+
+    #[test_log::test]
+    fn test_energy_breakdown() {
+        let (rows, cols, m) = parse_input(include_str!("../example.txt").into());
+        let mut map = LightMap::new(&m, rows, cols);
+        map.send_light(0, 0, Direction::Right);
+
+        let (up, down, left, right, total) = map.energy_breakdown();
+        assert_eq!((up, down, left, right), (10, 18, 12, 11));
+        assert_eq!(total, 46);
+        assert_eq!(map.count_energy(), 46);
+
+        // every energized tile carries at least one beam, some carry several
+        assert!(up + down + left + right >= total);
+    }
+
+    #[test_log::test]
+    fn test_max_energy() {
+        let (rows, cols, m) = parse_input(include_str!("../example.txt").into());
+        let mut map = LightMap::new(&m, rows, cols);
+
+        assert_eq!(map.max_energy(), (0, 3, Direction::Down, 51));
+
+        // energy is independent of any previous beams once reset
+        map.send_light(0, 0, Direction::Right);
+        assert_eq!(map.count_energy(), 46);
+        assert_eq!(map.energy_for_beam(0, 3, Direction::Down), 51);
+        assert_eq!(map.count_energy(), 46);
+
+        map.reset_energy();
+        assert_eq!(map.count_energy(), 0);
+        map.send_light(0, 3, Direction::Down);
+        assert_eq!(map.count_energy(), 51);
+    }
+
+    #[test_log::test]
+    fn test_solve() {
+        let example = include_str!("../example.txt");
+        assert_eq!(
+            solve(std::io::Cursor::new(example)).expect("valid"),
+            (46, 51)
+        );
+        assert_eq!(
+            solve(std::io::Cursor::new(format!("{}\n", example))).expect("valid"),
+            (46, 51)
+        );
+    }
+
     #[test_log::test]
     fn test_part2() {
         assert_eq!(part2(include_str!("../example.txt")), 51);
@@ -10,7 +10,17 @@ fn part1() {
     aoc17::part1(black_box(include_str!("../input.txt")));
 }
 
+#[divan::bench]
+fn part1_astar() {
+    aoc17::part1_astar(black_box(include_str!("../input.txt")));
+}
+
 #[divan::bench]
 fn part2() {
     aoc17::part2(black_box(include_str!("../input.txt")));
 }
+
+#[divan::bench]
+fn part2_astar() {
+    aoc17::part2_astar(black_box(include_str!("../input.txt")));
+}
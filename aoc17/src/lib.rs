@@ -1,7 +1,25 @@
-use ndarray::{Array, Array2};
-use pathfinding::directed::dijkstra::dijkstra;
+use grid::{Direction, Grid};
 use tracing::{info, trace};
 
+/// A rectangular map where every position has a heat loss (weight)
+pub trait WeightedGrid {
+    /// Weight of entering `pos`, None if `pos` is outside the grid
+    fn weight(&self, pos: (usize, usize)) -> Option<usize>;
+
+    /// Returns (rows, cols)
+    fn dims(&self) -> (usize, usize);
+}
+
+impl WeightedGrid for Grid<i32> {
+    fn weight(&self, pos: (usize, usize)) -> Option<usize> {
+        self.get(pos).map(|v| *v as usize)
+    }
+
+    fn dims(&self) -> (usize, usize) {
+        self.dim()
+    }
+}
+
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Copy)]
 enum Allow {
     Any,
@@ -14,6 +32,7 @@ struct Location {
     row: usize,
     col: usize,
     allow: Allow,
+    last: Option<Direction>, // only tracked if U-turns are allowed
 }
 
 impl Location {
@@ -21,39 +40,59 @@ impl Location {
         (self.row, self.col)
     }
 
-    // Try to move current location
-    fn constrained_move(&self, delta: (i32, i32), max: (usize, usize)) -> Option<Location> {
-        let target_row = delta.0 + (self.row as i32);
-        let target_col = delta.1 + (self.col as i32);
-
-        let allow = match self.allow {
-            Allow::Any if delta.0 == 0 => Allow::UpDown,
-            Allow::Any if delta.1 == 0 => Allow::LeftRight,
-            Allow::UpDown if delta.1 == 0 => Allow::LeftRight,
-            Allow::LeftRight if delta.0 == 0 => Allow::UpDown,
+    // Try to move current location. Going back the way we came is only
+    // possible if `u_turns` is set.
+    fn constrained_move<G: WeightedGrid>(
+        &self,
+        d: Direction,
+        amount: usize,
+        grid: &G,
+        u_turns: bool,
+    ) -> Option<Location> {
+        let allow = match (self.allow, d) {
+            (Allow::Any | Allow::LeftRight, Direction::Left | Direction::Right) => Allow::UpDown,
+            (Allow::Any | Allow::UpDown, Direction::Up | Direction::Down) => Allow::LeftRight,
+            _ if u_turns && self.last == Some(d.inverse()) => self.allow,
             _ => return None,
         };
 
-        (target_row >= 0
-            && target_col >= 0
-            && (target_row as usize) < max.0
-            && (target_col as usize) < max.1)
-            .then(|| Location {
-                row: target_row as usize,
-                col: target_col as usize,
-                allow,
-            })
+        let (dr, dc) = d.delta();
+        let row = self.row.checked_add_signed(dr as isize * amount as isize)?;
+        let col = self.col.checked_add_signed(dc as isize * amount as isize)?;
+        let (rows, cols) = grid.dims();
+
+        (row < rows && col < cols).then_some(Location {
+            row,
+            col,
+            allow,
+            last: u_turns.then_some(d),
+        })
     }
 }
 
 #[derive(Debug, PartialEq)]
-struct Solver {
-    values: Array2<i32>,
+struct Solver<G> {
+    values: G,
     min_len: usize,
     max_len: usize,
+    u_turns: bool,
 }
 
-impl Solver {
+impl<G: WeightedGrid> Solver<G> {
+    /// A solver where moving back the way we came is forbidden
+    fn new(values: G, min_len: usize, max_len: usize) -> Self {
+        Self::with_u_turns(values, min_len, max_len, false)
+    }
+
+    fn with_u_turns(values: G, min_len: usize, max_len: usize, u_turns: bool) -> Self {
+        Self {
+            values,
+            min_len,
+            max_len,
+            u_turns,
+        }
+    }
+
     /// Computes the weight between pos and other,
     /// NOT including pos weight, but INCLUDING other weight
     fn weight(&self, pos: &Location, mut other: Location) -> usize {
@@ -61,10 +100,10 @@ impl Solver {
         let mut total = 0;
 
         while other.position() != pos.position() {
-            total += *self
+            total += self
                 .values
-                .get(other.position())
-                .expect("valid position in map") as usize;
+                .weight(other.position())
+                .expect("valid position in map");
             if pos.row == other.row {
                 if pos.col > other.col {
                     other.col += 1;
@@ -89,17 +128,9 @@ impl Solver {
     //
     // retunrs the weight INCLUDING the end, but NOT including the start
     fn successors(&self, pos: &Location) -> Vec<(Location, usize)> {
-        let edge = self.values.dim();
         let deltas = (self.min_len..=self.max_len)
-            .flat_map(|v| {
-                [
-                    (0, -(v as i32)),
-                    (0, v as i32),
-                    (-(v as i32), 0),
-                    (v as i32, 0),
-                ]
-            })
-            .filter_map(|c| pos.constrained_move(c, edge))
+            .flat_map(|v| Direction::all().map(|d| (d, v)))
+            .filter_map(|(d, v)| pos.constrained_move(d, v, &self.values, self.u_turns))
             .map(|p| (p, self.weight(pos, p)))
             .collect();
 
@@ -109,23 +140,47 @@ impl Solver {
     }
 
     fn shortest_path_to_end(&self, pos: Location) -> usize {
-        let d = self.values.dim();
+        let d = self.values.dims();
         let (target_row, target_col) = (d.0 - 1, d.1 - 1);
 
         // start with a particular location and try to reach the goal
-        let result = dijkstra(
+        let cost = search::dijkstra_cost(
             &pos,
             |p| self.successors(p),
-            |p| (p.row == target_row && p.col == target_col),
-        );
+            |p| p.row == target_row && p.col == target_col,
+        )
+        .expect("Dijkstra finds a solution");
+        info!("Shortest path cost: {}", cost);
+        cost
+    }
 
-        let solution = result.expect("Dijkstra finds a solution");
-        info!("Shortest path:\n{:#?}", solution);
-        solution.1
+    /// Same as [Solver::shortest_path_to_end], using the manhattan distance
+    /// to the end as A* heuristic.
+    ///
+    /// Every step costs at least the cheapest cell, so the distance is scaled
+    /// by that cost to never overestimate.
+    fn shortest_path_astar(&self, pos: Location) -> usize {
+        let d = self.values.dims();
+        let (target_row, target_col) = (d.0 - 1, d.1 - 1);
+        let min_cost = (0..d.0)
+            .flat_map(|row| (0..d.1).map(move |col| (row, col)))
+            .filter_map(|p| self.values.weight(p))
+            .min()
+            .unwrap_or(0);
+
+        let cost = search::astar_cost(
+            &pos,
+            |p| self.successors(p),
+            |p| min_cost * ((target_row - p.row) + (target_col - p.col)),
+            |p| p.row == target_row && p.col == target_col,
+        )
+        .expect("A* finds a solution");
+        info!("Shortest path cost: {}", cost);
+        cost
     }
 }
 
-fn parse_input(input: &str) -> Array2<i32> {
+fn parse_input(input: &str) -> Grid<i32> {
     let lines = input.split('\n').collect::<Vec<_>>();
 
     let rows = lines.len();
@@ -134,7 +189,7 @@ fn parse_input(input: &str) -> Array2<i32> {
     let data = lines
         .iter()
         .flat_map(|l| {
-            l.chars().into_iter().map(|c| {
+            l.chars().map(|c| {
                 [c].iter()
                     .collect::<String>()
                     .parse::<i32>()
@@ -143,52 +198,71 @@ fn parse_input(input: &str) -> Array2<i32> {
         })
         .collect::<Vec<_>>();
 
-    let result = Array::from_shape_vec((rows, cols), data).expect("valid input");
+    let result = Grid::new(rows, cols, data);
 
     info!("Input:\n{:#?}", result);
 
     result
 }
 
+const START: Location = Location {
+    row: 0,
+    col: 0,
+    allow: Allow::Any,
+    last: None,
+};
+
+fn part1_solver(input: &str) -> Solver<Grid<i32>> {
+    Solver::new(parse_input(input), 1, 3)
+}
+
+fn part2_solver(input: &str) -> Solver<Grid<i32>> {
+    Solver::new(parse_input(input), 4, 10)
+}
+
+/// Minimal heat loss to go from the top-left to the bottom-right of `grid`,
+/// moving between `min_len` and `max_len` steps in a straight line before
+/// turning.
+pub fn min_heat_loss<G: WeightedGrid>(grid: G, min_len: usize, max_len: usize) -> usize {
+    Solver::new(grid, min_len, max_len).shortest_path_to_end(START)
+}
+
+/// Same as [min_heat_loss], however `u_turns` selects if the crucible may
+/// turn around and go back the way it came.
+pub fn min_heat_loss_with_u_turns<G: WeightedGrid>(
+    grid: G,
+    min_len: usize,
+    max_len: usize,
+    u_turns: bool,
+) -> usize {
+    Solver::with_u_turns(grid, min_len, max_len, u_turns).shortest_path_to_end(START)
+}
+
 pub fn part1(input: &str) -> usize {
-    let solver = Solver {
-        values: parse_input(input),
-        min_len: 1,
-        max_len: 3,
-    };
+    part1_solver(input).shortest_path_to_end(START)
+}
 
-    solver.shortest_path_to_end(Location {
-        row: 0,
-        col: 0,
-        allow: Allow::Any,
-    })
+pub fn part1_astar(input: &str) -> usize {
+    part1_solver(input).shortest_path_astar(START)
 }
 
 pub fn part2(input: &str) -> usize {
-    let solver = Solver {
-        values: parse_input(input),
-        min_len: 4,
-        max_len: 10,
-    };
+    part2_solver(input).shortest_path_to_end(START)
+}
 
-    solver.shortest_path_to_end(Location {
-        row: 0,
-        col: 0,
-        allow: Allow::Any,
-    })
+pub fn part2_astar(input: &str) -> usize {
+    part2_solver(input).shortest_path_astar(START)
 }
 
 #[cfg(test)]
 mod tests {
-    use ndarray::arr2;
-
     use super::*;
 
     #[test_log::test]
     fn test_input_parse() {
         assert_eq!(
             parse_input("123\n321\n888\n223"),
-            arr2(&[[1, 2, 3], [3, 2, 1], [8, 8, 8], [2, 2, 3]])
+            Grid::new(4, 3, vec![1, 2, 3, 3, 2, 1, 8, 8, 8, 2, 2, 3])
         );
     }
 
@@ -197,6 +271,62 @@ mod tests {
         assert_eq!(part1(include_str!("../example.txt")), 102);
     }
 
+    #[test_log::test]
+    fn test_weighted_grid() {
+        // every position has the same weight
+        struct Uniform {
+            rows: usize,
+            cols: usize,
+            weight: usize,
+        }
+
+        impl WeightedGrid for Uniform {
+            fn weight(&self, (row, col): (usize, usize)) -> Option<usize> {
+                (row < self.rows && col < self.cols).then_some(self.weight)
+            }
+
+            fn dims(&self) -> (usize, usize) {
+                (self.rows, self.cols)
+            }
+        }
+
+        let grid = |rows, cols| Uniform {
+            rows,
+            cols,
+            weight: 2,
+        };
+        assert_eq!(min_heat_loss(grid(5, 5), 1, 3), 16);
+        assert_eq!(min_heat_loss(grid(5, 5), 4, 10), 16);
+
+        assert_eq!(
+            min_heat_loss(parse_input(include_str!("../example.txt")), 1, 3),
+            102
+        );
+    }
+
+    #[test_log::test]
+    fn test_u_turns() {
+        // without U-turns, the only way to the end is through the 9s
+        let grid = parse_input("11111\n99999");
+
+        assert_eq!(min_heat_loss_with_u_turns(grid.clone(), 1, 3, false), 21);
+        assert_eq!(min_heat_loss(grid.clone(), 1, 3), 21);
+
+        // right 3, back left 1, right 2, then down
+        assert_eq!(min_heat_loss_with_u_turns(grid, 1, 3, true), 15);
+    }
+
+    #[test_log::test]
+    fn test_astar() {
+        assert_eq!(part1_astar(include_str!("../example.txt")), 102);
+        assert_eq!(part2_astar(include_str!("../example.txt")), 94);
+
+        // zero cost cells must not make the heuristic overestimate
+        let zeros = "19000\n19990\n00090\n09999\n00000";
+        assert_eq!(part1_astar(zeros), part1(zeros));
+        assert_eq!(part1(zeros), 10);
+    }
+
     #[test_log::test]
     fn test_part2() {
         assert_eq!(
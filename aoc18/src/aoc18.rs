@@ -16,12 +16,14 @@ fn main() {
         .with(EnvFilter::from_default_env())
         .init();
 
-    let s1 = aoc18::part1(include_str!("../input.txt"));
+    let input = runner::load_input(include_str!("../input.txt"));
+
+    let s1 = aoc18::part1(&input);
     println!("Part 1:  {}", s1);
 
-    let s1 = aoc18::part1_b(include_str!("../input.txt"));
+    let s1 = aoc18::part1_b(&input);
     println!("Part 1b: {}", s1);
 
-    let s2 = aoc18::part2(include_str!("../input.txt"));
+    let s2 = aoc18::part2(&input);
     println!("Part 2:  {}", s2);
 }
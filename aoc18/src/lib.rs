@@ -2,6 +2,7 @@ use std::{
     collections::{BTreeMap, HashSet},
     fmt::{Debug, Display, Write},
     hash::Hash,
+    io::{self, BufRead},
     ops::Add,
 };
 
@@ -46,7 +47,7 @@ impl Add<(i64, i64)> for Direction {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-struct DigInstruction<'a> {
+pub struct DigInstruction<'a> {
     direction: Direction,
     distance: i64, // always positive, but easier math
     color: &'a str,
@@ -77,6 +78,9 @@ struct DigMap<'a> {
     row_range: (i64, i64),                // upper range is exclusive
     col_range: (i64, i64),                // upper range is exclusive
 
+    // holes where the dig path continues to the hole above
+    north_connected: HashSet<(i64, i64)>,
+
     // digger position
     digger_pos: (i64, i64),
 }
@@ -91,6 +95,7 @@ impl<'a> DigMap<'a> {
             holes,
             row_range: (0, 1),
             col_range: (0, 1),
+            north_connected: HashSet::new(),
             digger_pos,
         }
     }
@@ -98,7 +103,15 @@ impl<'a> DigMap<'a> {
     fn perform_instructions(&mut self, instructions: &[DigInstruction<'a>]) {
         for instruction in instructions {
             for _ in 0..instruction.distance {
+                let previous = self.digger_pos;
                 self.digger_pos = instruction.direction + self.digger_pos;
+
+                match instruction.direction {
+                    Direction::Up => self.north_connected.insert(previous),
+                    Direction::Down => self.north_connected.insert(self.digger_pos),
+                    _ => false,
+                };
+
                 self.holes.insert(self.digger_pos, instruction.color);
 
                 if self.row_range.0 > self.digger_pos.0 {
@@ -122,26 +135,34 @@ impl<'a> DigMap<'a> {
         self.holes.contains_key(&p)
     }
 
-    fn find_inside(&self) -> (i64, i64) {
+    /// Finds a point that is not dug out and is inside the dig loop.
+    ///
+    /// Uses a scanline parity test: a point is inside if crossing the
+    /// loop an odd number of times when going to the left of it. Only
+    /// holes connected upwards count as crossings, so that following
+    /// along a horizontal edge is not counted twice.
+    ///
+    /// Returns None if the loop has no inside points.
+    fn find_inside(&self) -> Option<(i64, i64)> {
         for row in self.row_range.0..self.row_range.1 {
+            let mut crossings = 0;
             for col in self.col_range.0..self.col_range.1 {
                 let p = (row, col);
 
-                if !self.hole_at(Direction::Left + p)
-                    && self.hole_at(p)
-                    && !self.hole_at(Direction::Right + p)
-                {
-                    return Direction::Right + p;
+                if self.north_connected.contains(&p) {
+                    crossings += 1;
+                } else if !self.hole_at(p) && crossings % 2 == 1 {
+                    return Some(p);
                 }
             }
         }
-        panic!("If all is stairs, this is not implemented");
+        None
     }
 
     fn flood_fill_inside(&mut self) {
         let mut fills = Vec::new();
         let mut seen = HashSet::new();
-        fills.push(self.find_inside());
+        fills.extend(self.find_inside());
 
         while let Some(p) = fills.pop() {
             seen.insert(p);
@@ -188,7 +209,7 @@ impl<'a> Display for DigMap<'a> {
     }
 }
 
-fn instruction(input: &str) -> IResult<&str, DigInstruction> {
+fn instruction(input: &str) -> IResult<&str, DigInstruction<'_>> {
     tuple((
         alt((
             value(Direction::Up, tag("U")),
@@ -212,7 +233,7 @@ fn instruction(input: &str) -> IResult<&str, DigInstruction> {
     .parse(input)
 }
 
-fn parse_input(input: &str) -> Vec<DigInstruction> {
+pub fn parse_input(input: &str) -> Vec<DigInstruction<'_>> {
     let (r, result) = separated_list1(line_ending, instruction)
         .parse(input)
         .expect("valid input");
@@ -220,6 +241,17 @@ fn parse_input(input: &str) -> Vec<DigInstruction> {
     result
 }
 
+/// Same as [parse_input], however reads the input from a reader.
+///
+/// The read text is kept in `buffer`, as instructions reference it.
+pub fn parse_input_reader<R: BufRead>(
+    mut r: R,
+    buffer: &mut String,
+) -> io::Result<Vec<DigInstruction<'_>>> {
+    r.read_to_string(buffer)?;
+    Ok(parse_input(buffer.trim_end()))
+}
+
 type Point = (i64, i64);
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
@@ -460,7 +492,7 @@ impl DigMap2 {
             .inspect(|d| {
                 trace!("2P: {:?}", d);
             })
-            .map(|(a, b)| (a.1 * b.0 - b.1 * a.0))
+            .map(|(a, b)| a.1 * b.0 - b.1 * a.0)
             .inspect(|d| {
                 trace!(" ==> {:?}", d);
             })
@@ -473,10 +505,45 @@ impl DigMap2 {
     }
 }
 
-#[instrument(skip_all)]
-pub fn part1(input: &str) -> usize {
+/// Renders the trench dug by the given instructions (without filling
+/// the inside) as a `#`/`.` map, with the top-left corner at the origin.
+///
+/// NOTE: NOT ok for large maps.
+pub fn render(instructions: &[DigInstruction]) -> String {
     let mut map = DigMap::new();
-    map.perform_instructions(&parse_input(input));
+    map.perform_instructions(instructions);
+    map.to_string()
+}
+
+/// Splits the dug out volume into `(perimeter, interior, total)`, where
+/// `total = interior + perimeter`.
+///
+/// Uses the shoelace formula for the area enclosed by the trench center
+/// line and Pick's theorem for the cells fully inside of it.
+pub fn trench_metrics(instructions: &[DigInstruction]) -> (i128, i128, i128) {
+    let mut pos: (i128, i128) = (0, 0);
+    let mut perimeter = 0;
+    let mut twice_area = 0;
+
+    for instruction in instructions {
+        let (dr, dc) = instruction.direction.tuple();
+        let distance = instruction.distance as i128;
+        let next = (pos.0 + dr as i128 * distance, pos.1 + dc as i128 * distance);
+
+        twice_area += pos.1 * next.0 - next.1 * pos.0;
+        perimeter += distance;
+        pos = next;
+    }
+
+    // Pick: area = interior + perimeter / 2 - 1
+    let interior = twice_area.abs() / 2 - perimeter / 2 + 1;
+
+    (perimeter, interior, interior + perimeter)
+}
+
+fn flood_fill_volume(instructions: &[DigInstruction]) -> usize {
+    let mut map = DigMap::new();
+    map.perform_instructions(instructions);
     //info!("DigMap:\n{}", &map);
     map.flood_fill_inside();
 
@@ -484,6 +551,17 @@ pub fn part1(input: &str) -> usize {
     map.dug_out_depth()
 }
 
+#[instrument(skip_all)]
+pub fn part1(input: &str) -> usize {
+    flood_fill_volume(&parse_input(input))
+}
+
+/// Same as [part1], however reads the input from a reader
+pub fn part1_reader<R: BufRead>(r: R) -> io::Result<usize> {
+    let mut buffer = String::new();
+    Ok(flood_fill_volume(&parse_input_reader(r, &mut buffer)?))
+}
+
 #[instrument(skip_all)]
 pub fn part1_b(input: &str) -> u64 {
     let mut map = DigMap2::default();
@@ -494,9 +572,9 @@ pub fn part1_b(input: &str) -> u64 {
     map.area_from_points()
 }
 
-pub fn part2(input: &str) -> usize {
+fn color_distance_area(instructions: &[DigInstruction]) -> usize {
     let mut adjusted = Vec::new();
-    for i in parse_input(input) {
+    for i in instructions {
         adjusted.push(i.color_to_distance());
     }
 
@@ -505,8 +583,20 @@ pub fn part2(input: &str) -> usize {
     map.area_from_points() as usize
 }
 
+pub fn part2(input: &str) -> usize {
+    color_distance_area(&parse_input(input))
+}
+
+/// Same as [part2], however reads the input from a reader
+pub fn part2_reader<R: BufRead>(r: R) -> io::Result<usize> {
+    let mut buffer = String::new();
+    Ok(color_distance_area(&parse_input_reader(r, &mut buffer)?))
+}
+
 #[cfg(test)]
 mod tests {
+    use std::io::Cursor;
+
     use super::*;
 
     #[test_log::test]
@@ -514,6 +604,75 @@ mod tests {
         assert_eq!(part1(include_str!("../example.txt")), 62);
     }
 
+    #[test_log::test]
+    fn test_render() {
+        assert_eq!(
+            render(&parse_input(include_str!("../example.txt"))),
+            "
+#######
+#.....#
+###...#
+..#...#
+..#...#
+###.###
+#...#..
+##..###
+.#....#
+.######
+"
+            .trim_start()
+        );
+    }
+
+    #[test_log::test]
+    fn test_render_negative() {
+        assert_eq!(
+            render(&parse_input("L 2 (#123123)\nU 1 (#123123)")),
+            "#..\n###\n"
+        );
+    }
+
+    #[test_log::test]
+    fn test_staircase() {
+        // diamond made only of stairs:
+        //
+        // ..##..
+        // .####.
+        // ##..##
+        // ##..##
+        // .####.
+        // ..##..
+        let input = "
+R 1 (#123123)
+D 1 (#123123)
+R 1 (#123123)
+D 1 (#123123)
+R 1 (#123123)
+D 1 (#123123)
+L 1 (#123123)
+D 1 (#123123)
+L 1 (#123123)
+D 1 (#123123)
+L 1 (#123123)
+U 1 (#123123)
+L 1 (#123123)
+U 1 (#123123)
+L 1 (#123123)
+U 1 (#123123)
+R 1 (#123123)
+U 1 (#123123)
+R 1 (#123123)
+U 1 (#123123)
+        "
+        .trim();
+        assert_eq!(part1(input), 24);
+        assert_eq!(part1_b(input), 24);
+
+        // a loop without any inside points
+        let input = "R 1 (#123123)\nD 1 (#123123)\nL 1 (#123123)\nU 1 (#123123)";
+        assert_eq!(part1(input), 4);
+    }
+
     #[test_log::test]
     fn test_part1_b() {
         assert_eq!(part1_b(include_str!("../example.txt")), 62);
@@ -581,4 +740,36 @@ U 2 (#123123)
     fn test_part2() {
         assert_eq!(part2(include_str!("../example.txt")), 952408144115);
     }
+
+    #[test]
+    fn test_trench_metrics() {
+        let instructions = parse_input(include_str!("../example.txt"));
+
+        let (perimeter, interior, total) = trench_metrics(&instructions);
+        assert_eq!((perimeter, interior, total), (38, 24, 62));
+        assert_eq!(
+            perimeter + interior,
+            part1(include_str!("../example.txt")) as i128
+        );
+
+        let decoded = instructions
+            .iter()
+            .map(|i| i.color_to_distance())
+            .collect::<Vec<_>>();
+        let (perimeter, interior, total) = trench_metrics(&decoded);
+        assert_eq!(perimeter, decoded.iter().map(|i| i.distance as i128).sum());
+        assert_eq!(perimeter + interior, total);
+        assert_eq!(total, 952408144115);
+    }
+
+    #[test]
+    fn test_reader() {
+        let example = format!("{}\n", include_str!("../example.txt"));
+
+        assert_eq!(part1_reader(Cursor::new(&example)).expect("valid"), 62);
+        assert_eq!(
+            part2_reader(Cursor::new(&example)).expect("valid"),
+            952408144115
+        );
+    }
 }
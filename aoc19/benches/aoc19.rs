@@ -7,10 +7,10 @@ fn main() {
 
 #[divan::bench]
 fn part1() {
-    aoc19::part1(black_box(include_str!("../input.txt")));
+    aoc19::part1(black_box(include_str!("../input.txt"))).expect("valid input");
 }
 
 #[divan::bench]
 fn part2() {
-    aoc19::part2(black_box(include_str!("../input.txt")));
+    aoc19::part2(black_box(include_str!("../input.txt"))).expect("valid input");
 }
@@ -16,9 +16,9 @@ fn main() {
         .with(EnvFilter::from_default_env())
         .init();
 
-    let s1 = aoc19::part1(include_str!("../input.txt"));
-    println!("Part 1: {}", s1);
+    let input = runner::load_input(include_str!("../input.txt"));
+    let (s1, s2) = aoc19::solve(&input).expect("valid input");
 
-    let s2 = aoc19::part2(include_str!("../input.txt"));
+    println!("Part 1: {}", s1);
     println!("Part 2: {}", s2);
 }
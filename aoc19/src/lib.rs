@@ -10,6 +10,7 @@ use nom::{
     IResult, Parser,
 };
 use nom_supreme::ParserExt;
+use parse_error::{parse_all, ParseError};
 use tracing::{info, trace};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -20,6 +21,20 @@ enum Variable {
     S,
 }
 
+impl TryFrom<char> for Variable {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'x' => Ok(Variable::X),
+            'm' => Ok(Variable::M),
+            'a' => Ok(Variable::A),
+            's' => Ok(Variable::S),
+            _ => Err(format!("Invalid variable: {:?}", value)),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 struct Part {
     x: u64,
@@ -28,6 +43,9 @@ struct Part {
     s: u64,
 }
 
+/// A range of values, NOT including the upper bound
+type Range = (u64, u64);
+
 #[derive(Debug, Clone, PartialEq, Copy)]
 struct PartRange {
     x: (u64, u64), // NOT including the upper bound
@@ -52,6 +70,24 @@ impl PartRange {
             * (self.a.1 - self.a.0)
             * (self.s.1 - self.s.0)) as usize
     }
+
+    /// Same range, except `v` is limited to also be within `r`.
+    /// Returns None if nothing remains.
+    fn restricted(&self, v: Variable, r: Range) -> Option<PartRange> {
+        let mut result = *self;
+        let current = match v {
+            Variable::X => &mut result.x,
+            Variable::M => &mut result.m,
+            Variable::A => &mut result.a,
+            Variable::S => &mut result.s,
+        };
+        *current = (current.0.max(r.0), current.1.min(r.1));
+
+        if current.0 >= current.1 {
+            return None;
+        }
+        Some(result)
+    }
 }
 
 impl Part {
@@ -93,7 +129,7 @@ impl Condition {
     }
 
     /// Move the given range into accept/reject
-    fn split_range(&self, r: (u64, u64)) -> (Option<(u64, u64)>, Option<(u64, u64)>) {
+    fn split_range(&self, r: Range) -> (Option<Range>, Option<Range>) {
         if self.value < r.0 {
             // all values are larger than the target
             return match self.compare {
@@ -284,6 +320,38 @@ impl<'a> Solver<'a> {
 
         result
     }
+
+    /// Sum of ratings of all accepted parts
+    fn accepted_rating(&self, parts: &[Part]) -> usize {
+        let mut total = 0;
+
+        for p in parts.iter() {
+            if self.process(p) == FinalState::Accept {
+                info!("Accepted: {:?}", p);
+                total += p.rating()
+            } else {
+                info!("Rejected: {:?}", p);
+            }
+        }
+
+        total
+    }
+
+    /// Number of accepted parts where every rating is within `bounds`
+    /// (upper bound NOT included).
+    fn accepted_variations(&self, bounds: (u64, u64)) -> usize {
+        let meta_part = PartRange {
+            x: bounds,
+            m: bounds,
+            a: bounds,
+            s: bounds,
+        };
+
+        self.all_accepted(&meta_part)
+            .iter()
+            .map(|p| p.variations())
+            .sum()
+    }
 }
 
 impl<'a> From<&'a Input<'a>> for Solver<'a> {
@@ -321,13 +389,13 @@ fn label(s: &str) -> IResult<&str, &str> {
     take_while1(|c: char| c.is_alphabetic()).parse(s)
 }
 
-fn rule(s: &str) -> IResult<&str, Rule> {
+fn rule(s: &str) -> IResult<&str, Rule<'_>> {
     tuple((condition.terminated(tag(":")).opt(), label))
         .map(|(condition, target)| Rule { condition, target })
         .parse(s)
 }
 
-fn workflow(s: &str) -> IResult<&str, Workflow> {
+fn workflow(s: &str) -> IResult<&str, Workflow<'_>> {
     tuple((
         label,
         separated_list1(tag(","), rule)
@@ -338,18 +406,16 @@ fn workflow(s: &str) -> IResult<&str, Workflow> {
     .parse(s)
 }
 
-fn input(s: &str) -> Input {
-    let (r, i) = separated_pair(
-        separated_list1(line_ending, workflow),
-        tuple((line_ending, line_ending)),
-        separated_list1(line_ending, part),
+fn try_parse_input(s: &str) -> Result<Input<'_>, ParseError> {
+    parse_all(
+        s,
+        separated_pair(
+            separated_list1(line_ending, workflow),
+            tuple((line_ending, line_ending)),
+            separated_list1(line_ending, part),
+        )
+        .map(|(workflows, parts)| Input { workflows, parts }),
     )
-    .map(|(workflows, parts)| Input { workflows, parts })
-    .parse(s)
-    .expect("valid input");
-
-    assert_eq!(r, "");
-    i
 }
 
 fn part(s: &str) -> IResult<&str, Part> {
@@ -371,26 +437,30 @@ fn part(s: &str) -> IResult<&str, Part> {
     .parse(s)
 }
 
-pub fn part1(s: &str) -> usize {
-    let data = input(s);
+pub fn part1(s: &str) -> Result<usize, ParseError> {
+    let data = try_parse_input(s)?;
     let solver: Solver = (&data).into();
 
-    let mut total = 0;
+    Ok(solver.accepted_rating(&data.parts))
+}
 
-    for p in data.parts.iter() {
-        if solver.process(p) == FinalState::Accept {
-            info!("Accepted: {:?}", p);
-            total += p.rating()
-        } else {
-            info!("Rejected: {:?}", p);
-        }
-    }
+/// Number of accepted parts where every rating is within `bounds`
+/// (upper bound NOT included).
+pub fn count_accepted(s: &str, bounds: (u64, u64)) -> Result<usize, ParseError> {
+    let data = try_parse_input(s)?;
+    let solver: Solver = (&data).into();
 
-    total
+    Ok(solver.accepted_variations(bounds))
 }
 
-pub fn part2(s: &str) -> usize {
-    let data = input(s);
+/// Number of accepted parts (all ratings in `1..=4000`) where the rating
+/// named by `var` (one of `x`, `m`, `a`, `s`) is within `min..max`.
+///
+/// Panics on an invalid `var`.
+pub fn accepted_where(s: &str, var: char, min: u64, max: u64) -> Result<usize, ParseError> {
+    let var = Variable::try_from(var).expect("valid variable");
+
+    let data = try_parse_input(s)?;
     let solver: Solver = (&data).into();
 
     let meta_part = PartRange {
@@ -400,11 +470,27 @@ pub fn part2(s: &str) -> usize {
         s: (1, 4001),
     };
 
-    solver
+    Ok(solver
         .all_accepted(&meta_part)
         .iter()
+        .filter_map(|p| p.restricted(var, (min, max)))
         .map(|p| p.variations())
-        .sum()
+        .sum())
+}
+
+pub fn part2(s: &str) -> Result<usize, ParseError> {
+    count_accepted(s, (1, 4001))
+}
+
+/// Computes both the [part1] and [part2] answers, parsing the input only once.
+pub fn solve(s: &str) -> Result<(usize, usize), ParseError> {
+    let data = try_parse_input(s)?;
+    let solver: Solver = (&data).into();
+
+    Ok((
+        solver.accepted_rating(&data.parts),
+        solver.accepted_variations((1, 4001)),
+    ))
 }
 
 #[cfg(test)]
@@ -447,11 +533,188 @@ mod tests {
 
     #[test_log::test]
     fn test_part1() {
-        assert_eq!(part1(include_str!("../example.txt")), 19114);
+        assert_eq!(part1(include_str!("../example.txt")), Ok(19114));
+    }
+
+    fn brute_force_accepted(s: &str, bounds: (u64, u64)) -> usize {
+        let data = try_parse_input(s).expect("valid input");
+        let solver: Solver = (&data).into();
+
+        let mut count = 0;
+        for x in bounds.0..bounds.1 {
+            for m in bounds.0..bounds.1 {
+                for a in bounds.0..bounds.1 {
+                    for s in bounds.0..bounds.1 {
+                        if solver.process(&Part { x, m, a, s }) == FinalState::Accept {
+                            count += 1;
+                        }
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    /// Brute force over all the ratings in `1..=4000`, with `x` in `x_range`.
+    ///
+    /// Ratings only matter relative to condition values, so only one part is
+    /// checked for every combination of intervals between those values.
+    fn brute_force_accepted_where_x(s: &str, x_range: Range) -> usize {
+        let data = try_parse_input(s).expect("valid input");
+        let solver: Solver = (&data).into();
+
+        let intervals = |v: Variable, bounds: Range| {
+            let mut edges = vec![bounds.0, bounds.1];
+            for w in data.workflows.iter() {
+                for c in w.rules.iter().filter_map(|r| r.condition) {
+                    if c.variable == v {
+                        // LT splits before the value, GT just after it
+                        edges.push(c.value);
+                        edges.push(c.value + 1);
+                    }
+                }
+            }
+            edges.retain(|e| (bounds.0..=bounds.1).contains(e));
+            edges.sort();
+            edges.dedup();
+            edges.windows(2).map(|w| (w[0], w[1])).collect::<Vec<_>>()
+        };
+
+        let xs = intervals(Variable::X, x_range);
+        let ms = intervals(Variable::M, (1, 4001));
+        let as_ = intervals(Variable::A, (1, 4001));
+        let ss = intervals(Variable::S, (1, 4001));
+
+        let mut count = 0;
+        for x in xs.iter() {
+            for m in ms.iter() {
+                for a in as_.iter() {
+                    for s in ss.iter() {
+                        let part = Part {
+                            x: x.0,
+                            m: m.0,
+                            a: a.0,
+                            s: s.0,
+                        };
+                        if solver.process(&part) == FinalState::Accept {
+                            count +=
+                                ((x.1 - x.0) * (m.1 - m.0) * (a.1 - a.0) * (s.1 - s.0)) as usize;
+                        }
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    fn ranges_overlap(a: &PartRange, b: &PartRange) -> bool {
+        let overlap = |x: Range, y: Range| x.0 < y.1 && y.0 < x.1;
+
+        overlap(a.x, b.x) && overlap(a.m, b.m) && overlap(a.a, b.a) && overlap(a.s, b.s)
+    }
+
+    #[test]
+    fn test_ranges_overlap() {
+        let r = PartRange {
+            x: (1, 10),
+            m: (1, 10),
+            a: (1, 10),
+            s: (1, 10),
+        };
+        assert!(ranges_overlap(&r, &r));
+        assert!(ranges_overlap(&r, &PartRange { x: (9, 20), ..r }));
+        assert!(!ranges_overlap(&r, &PartRange { x: (10, 20), ..r }));
+        assert!(!ranges_overlap(&r, &PartRange { s: (1, 1), ..r }));
+    }
+
+    #[test]
+    fn test_accepted_ranges_disjoint() {
+        for input in [
+            include_str!("../example.txt"),
+            "in{x<5:A,x>4:sx,A}\nsx{x>5:A,x<6:R,A}\n\n{x=1,m=1,a=1,s=1}",
+        ] {
+            let data = try_parse_input(input).expect("valid input");
+            let solver: Solver = (&data).into();
+
+            let accepted = solver.all_accepted(&PartRange {
+                x: (1, 4001),
+                m: (1, 4001),
+                a: (1, 4001),
+                s: (1, 4001),
+            });
+            assert!(!accepted.is_empty());
+
+            for (idx, a) in accepted.iter().enumerate() {
+                for b in accepted.iter().skip(idx + 1) {
+                    assert!(!ranges_overlap(a, b), "{} overlaps {}", a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_accepted_where() {
+        let example = include_str!("../example.txt");
+
+        assert_eq!(accepted_where(example, 'x', 1, 4001), part2(example));
+        assert_eq!(accepted_where(example, 's', 4001, 5000), Ok(0));
+        assert_eq!(
+            accepted_where(example, 'x', 1000, 2000),
+            Ok(brute_force_accepted_where_x(example, (1000, 2000)))
+        );
+
+        let small = "in{x<1500:A,m>3:R,x>1800:sx,A}\nsx{s>2:A,R}\n\n{x=1,m=1,a=1,s=1}";
+        assert_eq!(
+            accepted_where(small, 'x', 1000, 2000),
+            Ok(brute_force_accepted_where_x(small, (1000, 2000)))
+        );
+        assert_eq!(
+            accepted_where(small, 'x', 1000, 2000),
+            Ok(500 * 4000 * 4000 * 4000 + 301 * 3 * 4000 * 4000 + 199 * 3 * 4000 * 3998)
+        );
+    }
+
+    #[test]
+    fn test_count_accepted() {
+        let example = include_str!("../example.txt");
+        assert_eq!(count_accepted(example, (1, 4001)), part2(example));
+        assert_eq!(
+            count_accepted(example, (1, 11)),
+            Ok(brute_force_accepted(example, (1, 11)))
+        );
+
+        let small = "in{x<5:A,m>3:R,a<7:sx,A}\nsx{s>2:A,R}\n\n{x=1,m=1,a=1,s=1}";
+        assert_eq!(
+            count_accepted(small, (1, 11)),
+            Ok(brute_force_accepted(small, (1, 11)))
+        );
+        assert_eq!(
+            count_accepted(small, (3, 9)),
+            Ok(brute_force_accepted(small, (3, 9)))
+        );
+    }
+
+    #[test]
+    fn test_truncated_input() {
+        let err = part1("in{x<5:A,R}\n\n{x=1,m=1,a=1,s=1}\n{x=2,m=").expect_err("truncated part");
+        assert_eq!(err.remaining, "\n{x=2,m=");
+        assert_eq!(err.line, 3);
+
+        let err = part2("in{x<5:A,R}\nsx{s>2:A").expect_err("truncated workflow");
+        assert!(err.remaining.contains("sx{s>2:A"));
     }
 
     #[test_log::test]
     fn test_part2() {
-        assert_eq!(part2(include_str!("../example.txt")), 167409079868000);
+        assert_eq!(part2(include_str!("../example.txt")), Ok(167409079868000));
+    }
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(
+            solve(include_str!("../example.txt")),
+            Ok((19114, 167409079868000))
+        );
+        assert!(solve("in{x<10:A,R}").is_err());
     }
 }
@@ -1,4 +1,4 @@
-use aoc2::{Bag, Game};
+use aoc2::Game;
 
 fn main() {
     // Run registered benchmarks.
@@ -7,17 +7,7 @@ fn main() {
 
 #[divan::bench]
 fn part1() {
-    let bag = Bag {
-        red: 12,
-        green: 13,
-        blue: 14,
-    };
-    divan::black_box(include_str!("../input.txt"))
-        .split('\n')
-        .filter_map(Game::parse)
-        .filter(|g| g.possible(&bag))
-        .map(|g| g.id)
-        .sum::<u32>();
+    aoc2::part1(divan::black_box(include_str!("../input.txt")));
 }
 
 #[divan::bench]
@@ -1,4 +1,4 @@
-use aoc2::{Bag, Game};
+use aoc2::Game;
 
 #[cfg(feature = "dhat-heap")]
 #[global_allocator]
@@ -9,21 +9,13 @@ fn main() {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
 
-    let bag = Bag {
-        red: 12,
-        green: 13,
-        blue: 14,
-    };
-    let id_sum: u32 = include_str!("../input.txt")
-        .split('\n')
-        .filter_map(Game::parse)
-        .filter(|g| g.possible(&bag))
-        .map(|g| g.id)
-        .sum();
+    let input = runner::load_input(include_str!("../input.txt"));
+
+    let id_sum = aoc2::part1(&input);
 
     println!("SUM of ID: {}", id_sum);
 
-    let power: u32 = include_str!("../input.txt")
+    let power: u32 = input
         .split('\n')
         .filter_map(Game::parse)
         .map(|g| g.min_bag().power())
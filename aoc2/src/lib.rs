@@ -1,4 +1,5 @@
 #[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Reveal {
     pub red: u32,
     pub green: u32,
@@ -6,6 +7,7 @@ pub struct Reveal {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bag {
     pub red: u32,
     pub green: u32,
@@ -13,8 +15,35 @@ pub struct Bag {
 }
 
 impl Bag {
+    /// The bag from the puzzle: 12 red, 13 green and 14 blue cubes
+    pub const STANDARD: Bag = Bag {
+        red: 12,
+        green: 13,
+        blue: 14,
+    };
+
     pub fn reveal_possible(&self, reveal: &Reveal) -> bool {
-        (self.red >= reveal.red) && (self.green >= reveal.green) && (self.blue >= reveal.blue)
+        self.check_reveal(reveal).is_ok()
+    }
+
+    /// Checks if the reveal fits in the bag.
+    ///
+    /// On failure returns every overflowing color as `(color, needed, available)`.
+    pub fn check_reveal(&self, r: &Reveal) -> Result<(), Vec<(&'static str, u32, u32)>> {
+        let overflows = [
+            ("red", r.red, self.red),
+            ("green", r.green, self.green),
+            ("blue", r.blue, self.blue),
+        ]
+        .into_iter()
+        .filter(|(_, needed, available)| needed > available)
+        .collect::<Vec<_>>();
+
+        if overflows.is_empty() {
+            Ok(())
+        } else {
+            Err(overflows)
+        }
     }
 
     pub fn power(&self) -> u32 {
@@ -58,6 +87,7 @@ impl From<&str> for Reveal {
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     pub id: u32,
     pub reveals: Vec<Reveal>,
@@ -84,7 +114,7 @@ impl Game {
     }
 
     pub fn possible(&self, bag: &Bag) -> bool {
-        self.reveals.iter().all(|r| bag.reveal_possible(r))
+        self.reveals.iter().all(|r| bag.check_reveal(r).is_ok())
     }
 
     pub fn min_bag(&self) -> Bag {
@@ -96,9 +126,56 @@ impl Game {
     }
 }
 
+/// Sum of the ids of all games in the input that are possible with `bag`
+pub fn sum_possible_ids(input: &str, bag: &Bag) -> u32 {
+    input
+        .split('\n')
+        .filter_map(Game::parse)
+        .filter(|g| g.possible(bag))
+        .map(|g| g.id)
+        .sum()
+}
+
+/// Same as [sum_possible_ids] using [Bag::STANDARD]
+pub fn part1(input: &str) -> u32 {
+    sum_possible_ids(input, &Bag::STANDARD)
+}
+
+/// Parses all games in the input and returns them as a JSON array
+#[cfg(feature = "serde")]
+pub fn games_json(input: &str) -> Result<String, String> {
+    let games = input
+        .split('\n')
+        .filter(|l| !l.is_empty())
+        .map(|l| Game::parse(l).ok_or_else(|| format!("Invalid game: {:?}", l)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    serde_json::to_string(&games).map_err(|e| format!("Failed to serialize: {}", e))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{Bag, Game, Reveal};
+    use crate::{part1, sum_possible_ids, Bag, Game, Reveal};
+
+    const EXAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[test]
+    fn test_sum_possible_ids() {
+        assert_eq!(sum_possible_ids(EXAMPLE, &Bag::STANDARD), 8);
+        assert_eq!(part1(EXAMPLE), 8);
+
+        let tiny = Bag {
+            red: 4,
+            green: 3,
+            blue: 6,
+        };
+        assert_eq!(sum_possible_ids(EXAMPLE, &tiny), 1 + 2);
+        assert_eq!(sum_possible_ids(EXAMPLE, &Bag::default()), 0);
+    }
 
     #[test]
     fn test_into() {
@@ -172,6 +249,19 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_round_trip() {
+        let line = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+        let game = Game::parse(line).expect("Valid");
+
+        let json = crate::games_json(line).expect("valid json");
+        let parsed: Vec<Game> = serde_json::from_str(&json).expect("valid games");
+
+        assert_eq!(parsed, vec![game]);
+        assert!(crate::games_json("Invalid").is_err());
+    }
+
     #[test]
     fn test_increase_game1() {
         let mut bag = Bag::default();
@@ -208,6 +298,25 @@ mod tests {
         assert_eq!(bag.power(), 630);
     }
 
+    #[test]
+    fn test_check_reveal() {
+        let bag = Bag {
+            red: 12,
+            green: 13,
+            blue: 14,
+        };
+
+        assert_eq!(bag.check_reveal(&Reveal::from("3 blue, 4 red")), Ok(()));
+        assert_eq!(
+            bag.check_reveal(&Reveal::from("8 green, 6 blue, 20 red")),
+            Err(vec![("red", 20, 12)])
+        );
+        assert_eq!(
+            bag.check_reveal(&Reveal::from("20 red, 15 blue")),
+            Err(vec![("red", 20, 12), ("blue", 15, 14)])
+        );
+    }
+
     #[test]
     fn test_reveal() {
         let bag = crate::Bag {
@@ -16,9 +16,9 @@ fn main() {
         .with(EnvFilter::from_default_env())
         .init();
 
-    let s1 = aoc20::part1(include_str!("../input.txt"));
-    println!("Part 1: {}", s1);
-
-    let s2 = aoc20::part2(include_str!("../input.txt"));
-    println!("Part 2: {}", s2);
+    runner::run(
+        include_str!("../input.txt"),
+        |s| aoc20::part1(s).expect("valid input"),
+        |s| aoc20::part2(s).expect("valid input"),
+    );
 }
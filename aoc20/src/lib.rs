@@ -1,4 +1,7 @@
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, BufRead},
+};
 
 use nom::{
     branch::alt,
@@ -10,6 +13,7 @@ use nom::{
     IResult, Parser,
 };
 use nom_supreme::ParserExt;
+use parse_error::{parse_all, ParseError};
 use tracing::{info, trace};
 
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -32,12 +36,51 @@ struct Input<'a> {
     modules: HashMap<&'a str, Module<'a>>,
 }
 
+impl<'a> Input<'a> {
+    /// Returns the single module sending pulses to `target`.
+    ///
+    /// Returns None if no module or more than one module targets it.
+    fn feeder_of(&self, target: &str) -> Option<&'a str> {
+        let mut feeders = self.inputs_of(target).into_iter();
+        match (feeders.next(), feeders.next()) {
+            (Some(feeder), None) => Some(feeder),
+            _ => None,
+        }
+    }
+
+    /// Returns all the modules sending pulses to `conj` (sorted by name)
+    fn inputs_of(&self, conj: &str) -> Vec<&'a str> {
+        let mut result = self
+            .modules
+            .values()
+            .filter(|m| m.targets.contains(&conj))
+            .map(|m| m.name)
+            .collect::<Vec<_>>();
+        result.sort();
+        result
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
-enum PulseState {
+pub enum PulseState {
     Low,
     High,
 }
 
+/// Receives every pulse delivered while handling a button press
+pub trait PulseSink {
+    fn deliver(&mut self, source: &str, target: &str, pulse: PulseState);
+}
+
+impl<F: FnMut(&str, &str, PulseState)> PulseSink for F {
+    fn deliver(&mut self, source: &str, target: &str, pulse: PulseState) {
+        self(source, target, pulse)
+    }
+}
+
+/// A [PulseSink] that ignores all pulses
+pub fn ignore_pulse(_source: &str, _target: &str, _pulse: PulseState) {}
+
 #[derive(Debug, Copy, Clone, PartialEq, Default)]
 struct FlipFlopState {
     on: bool,
@@ -55,7 +98,7 @@ enum ModuleState<'a> {
 }
 
 #[derive(Debug, Clone)]
-struct Solver<'a> {
+pub struct Solver<'a> {
     input: Input<'a>,
     state: HashMap<&'a str, ModuleState<'a>>,
     stopped: bool,
@@ -63,9 +106,35 @@ struct Solver<'a> {
 }
 
 impl<'a> Solver<'a> {
+    pub fn new(input: &'a str) -> Result<Self, ParseError> {
+        Ok(try_parse_input(input)?.into())
+    }
+
+    /// Returns the on/off state of every flip-flop module
+    pub fn flipflop_states(&self) -> HashMap<&'a str, bool> {
+        self.state
+            .iter()
+            .filter_map(|(name, state)| match state {
+                ModuleState::FlipFlop(f) => Some((*name, f.on)),
+                ModuleState::Conjunction(_) => None,
+            })
+            .collect()
+    }
+
+    /// Returns the last remembered pulse for every input of the given
+    /// conjunction module. None if `name` is not a conjunction.
+    pub fn conjunction_inputs(&self, name: &str) -> Option<&HashMap<&'a str, PulseState>> {
+        match self.state.get(name) {
+            Some(ModuleState::Conjunction(c)) => Some(&c.inputs),
+            _ => None,
+        }
+    }
+
     // Broadcasts a pulse and handles it. Returns the number of
-    // pulses sent around
-    fn pulse(&mut self) -> (usize, usize) {
+    // pulses sent around.
+    //
+    // Every delivered pulse (except the button one) is also sent to `sink`.
+    pub fn pulse<S: PulseSink>(&mut self, sink: &mut S) -> (usize, usize) {
         let mut instructions = VecDeque::new();
 
         let mut low_count = 0;
@@ -89,6 +158,7 @@ impl<'a> Solver<'a> {
                 PulseState::Low => low_count += 1,
                 PulseState::High => high_count += 1,
             }
+            sink.deliver(source, target, pulse);
             instructions.append(&mut self.send_pulse(source, target, pulse));
         }
         (low_count, high_count)
@@ -201,7 +271,7 @@ fn label(s: &str) -> IResult<&str, &str> {
     take_while1(|c: char| c.is_alphabetic()).parse(s)
 }
 
-fn module(i: &str) -> IResult<&str, Module> {
+fn module(i: &str) -> IResult<&str, Module<'_>> {
     separated_pair(
         alt((
             value((Operation::Broadcast, "broadcaster"), tag("broadcaster")),
@@ -223,11 +293,8 @@ fn module(i: &str) -> IResult<&str, Module> {
     .parse(i)
 }
 
-fn parse_input(s: &str) -> Input {
-    let (r, mvec) = separated_list1(line_ending, module)
-        .parse(s)
-        .expect("valid input");
-    assert_eq!(r, "");
+fn try_parse_input(s: &str) -> Result<Input<'_>, ParseError> {
+    let mvec = parse_all(s, separated_list1(line_ending, module))?;
 
     let mut broadcast_targets = None;
     let mut modules = HashMap::new();
@@ -239,19 +306,37 @@ fn parse_input(s: &str) -> Input {
         modules.insert(m.name, m);
     }
     let broadcast_targets = broadcast_targets.expect("has broadcast");
-    Input {
+    Ok(Input {
         broadcast_targets,
         modules,
-    }
+    })
+}
+
+/// Same as [try_parse_input], however reads the input from a reader.
+///
+/// The read text is kept in `buffer`, as modules reference it.
+fn parse_input_reader<R: BufRead>(mut r: R, buffer: &mut String) -> io::Result<Input<'_>> {
+    r.read_to_string(buffer)?;
+    try_parse_input(buffer.trim_end()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn part1(input: &str) -> Result<usize, ParseError> {
+    Ok(pulse_product(try_parse_input(input)?))
+}
+
+/// Same as [part1], however reads the input from a reader
+pub fn part1_reader<R: BufRead>(r: R) -> io::Result<usize> {
+    let mut buffer = String::new();
+    Ok(pulse_product(parse_input_reader(r, &mut buffer)?))
 }
 
-pub fn part1(input: &str) -> usize {
-    let mut solver: Solver = parse_input(input).into();
+fn pulse_product(input: Input) -> usize {
+    let mut solver: Solver = input.into();
 
     let mut low = 0;
     let mut high = 0;
     for _ in 0..1000 {
-        let (l, h) = solver.pulse();
+        let (l, h) = solver.pulse(&mut ignore_pulse);
         trace!(
             "-----------------TOTAL: {}, {} ------------------------",
             l,
@@ -264,56 +349,29 @@ pub fn part1(input: &str) -> usize {
     low * high
 }
 
-pub fn lcm(mut x: Vec<usize>) -> usize {
-    let mut v = x.pop().expect("non-empty vector");
-
-    while let Some(a) = x.pop() {
-        let b = v;
-
-        let mut ga = a;
-        let mut gb = b;
-
-        let gcd = 'calc: loop {
-            if ga > gb {
-                ga = ga % gb;
-                if ga == 0 {
-                    break 'calc gb;
-                }
-            } else {
-                gb = gb % ga;
-                if gb == 0 {
-                    break 'calc ga;
-                }
-            }
-        };
-
-        v = (a * b) / gcd;
-    }
-
-    v
+pub fn part2(input: &str) -> Result<usize, ParseError> {
+    Ok(presses_until_rx(try_parse_input(input)?))
 }
 
-pub fn part2(input: &str) -> usize {
-    let input = parse_input(input);
+/// Same as [part2], however reads the input from a reader
+pub fn part2_reader<R: BufRead>(r: R) -> io::Result<usize> {
+    let mut buffer = String::new();
+    Ok(presses_until_rx(parse_input_reader(r, &mut buffer)?))
+}
 
-    // Manual check:
-    //  rx gets value from &hb
-    //  hb gets values from:
-    //     - js, zb, bs, rr
+fn presses_until_rx(input: Input) -> usize {
+    // rx gets its value from a single conjunction, which in turn
+    // gets values from several modules (cycles that are LCM-ed)
     let mut to_low_output = Vec::new();
 
-    assert!(input.modules.get("rx").is_none());
+    assert!(!input.modules.contains_key("rx"));
 
-    let hb = input.modules.get("hb").expect("has HB");
-    assert_eq!(hb.operation, Operation::Conjunction);
-    assert_eq!(hb.targets, vec!["rx"]);
+    let feeder = input.feeder_of("rx").expect("rx has a single feeder");
+    let feeder = input.modules.get(feeder).expect("feeder is a module");
+    assert_eq!(feeder.operation, Operation::Conjunction);
+    assert_eq!(feeder.targets, vec!["rx"]);
 
-    for target in input
-        .modules
-        .values()
-        .filter(|m| m.targets.contains(&"hb"))
-        .map(|m| m.name)
-    {
+    for target in input.inputs_of(feeder.name) {
         // How costry is it to turn target to "High"
         info!("Waiting for Low output for: {:?}", target);
         let mut solver: Solver = input.clone().into();
@@ -323,18 +381,22 @@ pub fn part2(input: &str) -> usize {
         let mut cnt = 0;
         while !solver.stopped {
             cnt += 1;
-            solver.pulse();
+            solver.pulse(&mut ignore_pulse);
         }
         to_low_output.push(cnt);
     }
 
     info!("to_low_output: {:?}", to_low_output);
 
-    lcm(to_low_output)
+    to_low_output
+        .into_iter()
+        .fold(1, |acc, cnt| mathutil::lcm(acc as u64, cnt as u64) as usize)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io::Cursor;
+
     use rstest::rstest;
 
     use super::*;
@@ -352,7 +414,109 @@ mod tests {
 
     #[test_log::test]
     fn test_part1() {
-        assert_eq!(part1(include_str!("../example.txt")), 32000000);
-        assert_eq!(part1(include_str!("../example2.txt")), 11687500);
+        assert_eq!(part1(include_str!("../example.txt")), Ok(32000000));
+        assert_eq!(part1(include_str!("../example2.txt")), Ok(11687500));
+    }
+
+    #[test_log::test]
+    fn test_truncated_input() {
+        let err = part1("broadcaster -> a\n%a -> inv\n&inv -> ").expect_err("truncated");
+        assert_eq!(err.remaining, "\n&inv -> ");
+        assert_eq!(err.line, 2);
+
+        let err = part1_reader(Cursor::new("broadcaster -> a\n%a ->\n")).expect_err("truncated");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("%a ->"));
+    }
+
+    #[test_log::test]
+    fn test_module_state() {
+        let mut solver = Solver::new(include_str!("../example.txt")).expect("valid input");
+        solver.pulse(&mut ignore_pulse);
+        assert_eq!(
+            solver.flipflop_states(),
+            HashMap::from([("a", false), ("b", false), ("c", false)])
+        );
+        assert_eq!(
+            solver.conjunction_inputs("inv"),
+            Some(&HashMap::from([("c", PulseState::Low)]))
+        );
+        assert_eq!(solver.conjunction_inputs("a"), None);
+
+        let mut solver = Solver::new(include_str!("../example2.txt")).expect("valid input");
+        solver.pulse(&mut ignore_pulse);
+        assert_eq!(
+            solver.flipflop_states(),
+            HashMap::from([("a", true), ("b", true)])
+        );
+        assert_eq!(
+            solver.conjunction_inputs("con"),
+            Some(&HashMap::from([
+                ("a", PulseState::High),
+                ("b", PulseState::High)
+            ]))
+        );
+    }
+
+    #[test_log::test]
+    fn test_pulse_sink() {
+        let mut solver = Solver::new(include_str!("../example2.txt")).expect("valid input");
+
+        let mut low_to_output = 0;
+        let mut delivered = 0;
+        let mut sent = 0;
+        for _ in 0..10 {
+            let (l, h) = solver.pulse(&mut |_: &str, target: &str, pulse| {
+                delivered += 1;
+                if target == "output" && pulse == PulseState::Low {
+                    low_to_output += 1;
+                }
+            });
+            sent += l + h - 1; // button pulse is not delivered to the sink
+        }
+
+        // con sends a low pulse to output every second press
+        assert_eq!(low_to_output, 5);
+        assert_eq!(delivered, sent);
+    }
+
+    #[test_log::test]
+    fn test_feeders() {
+        let input = try_parse_input(
+            "
+broadcaster -> a, b
+%a -> hub, c
+%b -> hub
+%c -> a
+&hub -> out
+        "
+            .trim(),
+        )
+        .expect("valid input");
+
+        assert_eq!(input.feeder_of("out"), Some("hub"));
+        assert_eq!(input.inputs_of("hub"), vec!["a", "b"]);
+        assert_eq!(input.feeder_of("hub"), None);
+        assert_eq!(input.feeder_of("c"), Some("a"));
+        assert_eq!(input.inputs_of("a"), vec!["broadcaster", "c"]);
+        assert_eq!(input.feeder_of("broadcaster"), None);
+    }
+
+    #[test_log::test]
+    fn test_reader() {
+        assert_eq!(
+            part1_reader(Cursor::new(include_str!("../example.txt"))).expect("valid"),
+            32000000
+        );
+        assert_eq!(
+            part1_reader(Cursor::new(include_str!("../example2.txt"))).expect("valid"),
+            11687500
+        );
+
+        // part 2 relies on the shape of the actual input
+        assert_eq!(
+            part2_reader(Cursor::new(include_str!("../input.txt"))).expect("valid"),
+            part2(include_str!("../input.txt")).expect("valid")
+        );
     }
 }
@@ -16,12 +16,14 @@ fn main() {
         .with(EnvFilter::from_default_env())
         .init();
 
-    let s1 = aoc21::part1(include_str!("../input.txt"));
+    let input = runner::load_input(include_str!("../input.txt"));
+
+    let s1 = aoc21::part1(&input);
     println!("Part 1: {}", s1);
 
-    let s2 = aoc21::part2(include_str!("../input.txt"));
+    let s2 = aoc21::part2(&input);
     println!("Part 2:  {}", s2);
 
-    let s2 = aoc21::part2b(include_str!("../input.txt"));
+    let s2 = aoc21::part2b(&input);
     println!("Part 2B: {}", s2);
 }
@@ -1,9 +1,13 @@
 use std::collections::HashSet;
 
+use grid::parse_char_grid;
 use tracing::info;
 
 type Position = (i32, i32); // row, col
 
+// positions on the infinite grid can go far beyond the base grid
+type InfinitePosition = (i64, i64); // row, col
+
 #[derive(Debug, PartialEq, Copy, Clone)]
 enum Count {
     Odd,
@@ -32,17 +36,22 @@ struct Input {
 #[derive(Debug, Clone)]
 struct InfiniteStateIterator {
     input: Input,
-    seen: HashSet<Position>,
-    bfs: Vec<Position>, // current search location
-    count: Count,       // type of count we are looking for
-    matches: usize,     // matches for count
-    step: usize,        // existing step - 1
+    seen: HashSet<InfinitePosition>,
+    bfs: Vec<InfinitePosition>, // current search location
+    count: Count,               // type of count we are looking for
+    matches: usize,             // matches for count
+    step: usize,                // existing step - 1
 }
 
 impl InfiniteStateIterator {
     fn from(input: Input, count: Count) -> Self {
+        let start = (input.start.0 as i64, input.start.1 as i64);
+        Self::from_start(input, start, count)
+    }
+
+    fn from_start(input: Input, start: InfinitePosition, count: Count) -> Self {
         Self {
-            bfs: vec![input.start],
+            bfs: vec![start],
             input,
             count,
             seen: HashSet::new(),
@@ -51,21 +60,13 @@ impl InfiniteStateIterator {
         }
     }
 
-    fn directions(&self, p: Position) -> Vec<Position> {
+    fn directions(&self, p: InfinitePosition) -> Vec<InfinitePosition> {
         [(-1, 0), (1, 0), (0, -1), (0, 1)]
             .iter()
             .map(move |(r, c)| (*r + p.0, *c + p.1))
             .filter(|p| {
-                let mut r = p.0;
-                let mut c = p.1;
-                while r < 0 {
-                    r += self.input.rows as i32;
-                }
-                r %= self.input.rows as i32;
-                while c < 0 {
-                    c += self.input.cols as i32;
-                }
-                c %= self.input.cols as i32;
+                let r = p.0.rem_euclid(self.input.rows as i64) as i32;
+                let c = p.1.rem_euclid(self.input.cols as i64) as i32;
 
                 !self.input.stones.contains(&(r, c))
             })
@@ -111,8 +112,49 @@ impl Input {
     }
 
     fn count(&self, steps: usize, t: Count) -> usize {
+        let (even, odd) = self.count_both(steps);
+        match t {
+            Count::Even => even,
+            Count::Odd => odd,
+        }
+    }
+
+    /// Counts reachable positions for both parities using a single BFS.
+    ///
+    /// Returns (even, odd) counts.
+    fn count_both(&self, steps: usize) -> (usize, usize) {
+        let mut even = 0;
+        let mut odd = 0;
+
+        self.visit(steps, |_, step| {
+            if Count::Even.matches(step) {
+                even += 1;
+            } else {
+                odd += 1;
+            }
+        });
+
+        (even, odd)
+    }
+
+    /// Positions reachable in exactly `steps` steps
+    fn reachable(&self, steps: usize) -> HashSet<Position> {
+        let mut result = HashSet::new();
+
+        self.visit(steps, |p, step| {
+            if step % 2 == steps % 2 {
+                result.insert(p);
+            }
+        });
+
+        result
+    }
+
+    /// BFS from the start for the given number of steps, calling `found`
+    /// with every position the first time it is reached and the step
+    /// at which it was reached.
+    fn visit(&self, steps: usize, mut found: impl FnMut(Position, usize)) {
         let mut seen = HashSet::new();
-        let mut matched = 0;
 
         let mut bfs = Vec::new();
         bfs.push(self.start);
@@ -129,61 +171,103 @@ impl Input {
                     seen.insert(ns);
                     next_step.push(ns);
 
-                    if t.matches(step + 1) {
-                        matched += 1;
-                    }
+                    found(ns, step + 1);
                 }
             }
 
             bfs.append(&mut next_step);
         }
-
-        matched
     }
 }
 
 fn parse_input(input: &str) -> Input {
-    let mut rows = 0;
-    let mut cols = None;
+    let (data, rows, cols) = parse_char_grid(input);
     let mut start = None;
     let mut stones = HashSet::new();
 
-    for (row, l) in input.split('\n').enumerate().map(|(r, l)| (r as i32, l)) {
-        match cols {
-            Some(v) => assert!(l.len() == v),
-            None => cols = Some(l.len()),
-        }
-
-        for (col, c) in l.chars().enumerate().map(|(c, l)| (c as i32, l)) {
-            match c {
-                '.' => (),
-                '#' => {
-                    stones.insert((row, col));
-                }
-                'S' => {
-                    assert!(start.is_none());
-                    start = Some((row, col));
-                }
-                _ => panic!("Invalid input: '{}' is unknown", c),
+    for (pos, c) in data {
+        match c {
+            '.' => (),
+            '#' => {
+                stones.insert(pos);
+            }
+            'S' => {
+                assert!(start.is_none());
+                start = Some(pos);
             }
+            _ => panic!("Invalid input: '{}' is unknown", c),
         }
-
-        rows += 1;
     }
 
+    assert!(cols > 0, "valid input - has cols");
+
     Input {
         rows,
-        cols: cols.expect("valid input - has cols"),
+        cols,
         start: start.expect("valid input - has start"),
         stones,
     }
 }
 
+/// Renders the map with `O` on every plot reachable in exactly `steps` steps
+pub fn render_reachable(input: &str, steps: usize) -> String {
+    let input = parse_input(input);
+    let reachable = input.reachable(steps);
+
+    (0..input.rows as i32)
+        .map(|row| {
+            (0..input.cols as i32)
+                .map(|col| {
+                    let p = (row, col);
+                    if input.stones.contains(&p) {
+                        '#'
+                    } else if reachable.contains(&p) {
+                        'O'
+                    } else if input.start == p {
+                        'S'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+                + "\n"
+        })
+        .collect()
+}
+
 pub fn part1(input: &str) -> usize {
     let input = parse_input(input);
     input.count(64, Count::Even)
 }
 
+/// Fits `f(x) = a*x^2 + b*x + c` through the given samples, returning `(a, b, c)`.
+///
+/// X-values of the samples MUST be evenly spaced, which allows computing the
+/// coefficients from differences using only integer arithmetic.
+///
+/// Panics if the samples are not evenly spaced or the coefficients are
+/// not integers.
+pub fn quadratic_fit(samples: [(usize, usize); 3]) -> (i128, i128, i128) {
+    let [(x0, y0), (x1, y1), (x2, y2)] = samples.map(|(x, y)| (x as i128, y as i128));
+
+    let h = x1 - x0;
+    assert!(h != 0 && x2 - x1 == h, "samples must be evenly spaced");
+
+    // second difference is 2*a*h^2
+    let d2 = y2 - 2 * y1 + y0;
+    assert_eq!(d2 % (2 * h * h), 0, "non-integer x^2 coefficient");
+    let a = d2 / (2 * h * h);
+
+    // first difference is a*(x1^2 - x0^2) + b*h
+    let d1 = y1 - y0 - a * (x1 * x1 - x0 * x0);
+    assert_eq!(d1 % h, 0, "non-integer x coefficient");
+    let b = d1 / h;
+
+    let c = y0 - a * x0 * x0 - b * x0;
+
+    (a, b, c)
+}
+
 pub fn part2b(input: &str) -> usize {
     // Alternative implementation (slower, but maybe less error/off-by-one prone):
     //   Given fixed grid, do interpolation (seems like a linear sequence)
@@ -241,19 +325,16 @@ pub fn part2b(input: &str) -> usize {
     }
 
     const STEPS: usize = 26501365;
+    assert_eq!((STEPS - i.step) % (2 * 131), 0);
 
-    let mut steps = i.step;
-    let mut total = i.matches;
-    let mut to_add1 = b;
-    while steps < STEPS {
-        steps += 2 * 131;
-        to_add1 += c;
-        total += to_add1;
-    }
-
-    assert_eq!(steps, STEPS);
+    // last three samples, taken every 2*131 steps
+    let y2 = i.matches;
+    let y1 = y2 - b;
+    let y0 = y1 - (b - c);
+    let (a, b, c) = quadratic_fit([(0, y0), (1, y1), (2, y2)]);
 
-    total
+    let x = (2 + (STEPS - i.step) / (2 * 131)) as i128;
+    (a * x * x + b * x + c) as usize
 }
 
 pub fn part2(input: &str) -> usize {
@@ -276,13 +357,11 @@ pub fn part2(input: &str) -> usize {
     let n = input.rows as i32 - 1;
 
     // fully reachable (and from the center)
-    total += ((grid_width / 2) * 2 + 1)
-        * ((grid_width / 2) * 2 + 1)
-        * input.count(2 * (input.rows) + input.cols, Count::Odd);
+    let (full_even, full_odd) = input.count_both(2 * (input.rows) + input.cols);
+
+    total += ((grid_width / 2) * 2 + 1) * ((grid_width / 2) * 2 + 1) * full_odd;
 
-    total += (((grid_width + 1) / 2) * 2)
-        * (((grid_width + 1) / 2) * 2)
-        * input.count(2 * (input.rows) + input.cols, Count::Even);
+    total += (grid_width.div_ceil(2) * 2) * (grid_width.div_ceil(2) * 2) * full_even;
 
     //  Partial only reachable, using coordinates
 
@@ -346,8 +425,88 @@ mod tests {
         assert_eq!(input.count(6, Count::Even), 16);
     }
 
+    #[test]
+    fn test_count_both() {
+        let input = parse_input(include_str!("../example.txt"));
+
+        for steps in 0..10 {
+            assert_eq!(
+                input.count_both(steps),
+                (
+                    input.count(steps, Count::Even),
+                    input.count(steps, Count::Odd)
+                )
+            );
+        }
+        assert_eq!(input.count_both(6).0, 16);
+        assert_eq!(input.count_both(6).0, input.count(6, Count::Even));
+    }
+
+    #[test]
+    fn test_infinite_far_away() {
+        let input = parse_input(include_str!("../example.txt"));
+
+        let mut near = InfiniteStateIterator::from(input.clone(), Count::Even);
+
+        // same start, shifted by a whole number of grids past i32 range
+        let shift = (1_i64 << 33) * input.rows as i64;
+        let mut far = InfiniteStateIterator::from_start(
+            input.clone(),
+            (input.start.0 as i64 - shift, input.start.1 as i64 + shift),
+            Count::Even,
+        );
+
+        for _ in 0..50 {
+            near.step();
+            far.step();
+            assert_eq!(near.matches, far.matches);
+        }
+        assert!(far.matches > 0);
+    }
+
+    #[test]
+    fn test_render_reachable() {
+        let rendered = render_reachable(include_str!("../example.txt"), 2);
+
+        assert_eq!(rendered.chars().filter(|c| *c == 'O').count(), 4);
+        assert_eq!(
+            rendered,
+            "
+...........
+.....###.#.
+.###.##..#.
+..#.#O..#..
+....#.#....
+.##O.O####.
+.##.O#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........
+"
+            .trim_start()
+        );
+
+        assert_eq!(
+            render_reachable(include_str!("../example.txt"), 6)
+                .chars()
+                .filter(|c| *c == 'O')
+                .count(),
+            16
+        );
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(part1(include_str!("../example.txt")), 42);
     }
+
+    #[test]
+    fn test_quadratic_fit() {
+        assert_eq!(quadratic_fit([(0, 0), (1, 1), (2, 4)]), (1, 0, 0));
+        assert_eq!(quadratic_fit([(3, 9), (5, 25), (7, 49)]), (1, 0, 0));
+
+        // 2x^2 - 3x + 5
+        assert_eq!(quadratic_fit([(2, 7), (4, 25), (6, 59)]), (2, -3, 5));
+    }
 }
@@ -14,3 +14,13 @@ fn part1() {
 fn part2() {
     aoc22::part2(black_box(include_str!("../input.txt")));
 }
+
+#[divan::bench]
+fn par_part2() {
+    aoc22::par_part2(black_box(include_str!("../input.txt")));
+}
+
+#[divan::bench]
+fn analyze() {
+    aoc22::analyze(black_box(include_str!("../input.txt")));
+}
@@ -16,9 +16,5 @@ fn main() {
         .with(EnvFilter::from_default_env())
         .init();
 
-    let s1 = aoc22::part1(include_str!("../input.txt"));
-    println!("Part 1: {}", s1);
-
-    let s2 = aoc22::part2(include_str!("../input.txt"));
-    println!("Part 2: {}", s2);
+    runner::run(include_str!("../input.txt"), aoc22::part1, aoc22::part2);
 }
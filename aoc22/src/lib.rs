@@ -3,22 +3,25 @@ use std::{
     fmt::Debug,
 };
 
-use glam::IVec3;
+use glam::I64Vec3;
 use nom::{
     bytes::complete::tag,
-    character::complete::line_ending,
-    multi::separated_list1,
     sequence::{separated_pair, tuple},
     IResult, Parser,
 };
 use nom_supreme::ParserExt;
-use petgraph::Graph;
+use petgraph::{
+    dot::{Config, Dot},
+    visit::EdgeRef,
+    Graph,
+};
+use rayon::prelude::*;
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Brick {
     pub idx: usize,
-    pub start: IVec3,
-    pub end: IVec3,
+    pub start: I64Vec3,
+    pub end: I64Vec3,
 }
 
 impl Debug for Brick {
@@ -32,19 +35,26 @@ impl Debug for Brick {
 }
 
 impl Brick {
-    fn bottom_z(&self) -> i32 {
+    fn bottom_z(&self) -> i64 {
         self.start.z.min(self.end.z)
     }
 
-    fn top_z(&self) -> i32 {
+    fn top_z(&self) -> i64 {
         self.start.z.max(self.end.z)
     }
 
-    fn drop_z(&mut self, cnt: i32) {
+    fn drop_z(&mut self, cnt: i64) {
         self.start.z -= cnt;
         self.end.z -= cnt;
     }
 
+    /// The x,y columns covered by this brick
+    fn footprint(&self) -> impl Iterator<Item = (i64, i64)> {
+        let xs = self.start.x.min(self.end.x)..=self.start.x.max(self.end.x);
+        let ys = self.start.y.min(self.end.y)..=self.start.y.max(self.end.y);
+        xs.flat_map(move |x| ys.clone().map(move |y| (x, y)))
+    }
+
     fn intesects_xy(&self, other: &Brick) -> bool {
         if (self.end.x < other.start.x) || (other.end.x < self.start.x) {
             return false;
@@ -58,10 +68,14 @@ impl Brick {
 
 pub struct Building {
     pub bricks: Vec<Brick>,
-    by_top_z: HashMap<i32, Vec<usize>>, // z-index to brick index
-    by_bottom_z: HashMap<i32, Vec<usize>>, // z-index to brick index
+    by_top_z: HashMap<i64, Vec<usize>>, // z-index to brick index
+    by_bottom_z: HashMap<i64, Vec<usize>>, // z-index to brick index
+    heights: HashMap<(i64, i64), i64>,  // x,y column to topmost z
 }
 
+/// Bricks resting at this z are held up by the ground
+const GROUND_Z: i64 = 1;
+
 const LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
 
 pub fn idx_to_human(idx: usize) -> String {
@@ -136,6 +150,7 @@ impl Building {
             bricks: Vec::new(),
             by_top_z: HashMap::new(),
             by_bottom_z: HashMap::new(),
+            heights: HashMap::new(),
         };
 
         // make sure lower z items drop first
@@ -147,22 +162,34 @@ impl Building {
         result
     }
 
+    /// The bricks after they all dropped into place
+    pub fn settled_bricks(&self) -> &[Brick] {
+        &self.bricks
+    }
+
+    /// The topmost z for every x,y column that has a brick on it
+    pub fn heights(&self) -> HashMap<(i64, i64), i64> {
+        self.heights.clone()
+    }
+
     fn brick_with_index(&self, idx: usize) -> &Brick {
         self.bricks.get(idx).expect("Valid brick index")
     }
 
     fn drop_brick(&mut self, mut b: Brick) {
-        'drop_loop: while b.bottom_z() > 1 {
-            // check if we can drop one
-            if let Some(v) = self.by_top_z.get(&(b.bottom_z() - 1)) {
-                for other in v.iter().map(|idx| self.brick_with_index(*idx)) {
-                    if b.intesects_xy(other) {
-                        break 'drop_loop;
-                    }
-                }
-            }
-
-            b.drop_z(1);
+        // rest on top of the highest brick below, or on the ground. Bricks
+        // drop in bottom z order, so column heights are all below this brick
+        let rest_z = b
+            .footprint()
+            .filter_map(|p| self.heights.get(&p))
+            .map(|z| z + 1)
+            .max()
+            .unwrap_or(GROUND_Z);
+
+        b.drop_z(b.bottom_z() - rest_z);
+
+        for p in b.footprint() {
+            self.heights.insert(p, b.top_z());
         }
 
         let brick_idx = self.bricks.len();
@@ -186,7 +213,18 @@ impl Building {
         self.below_bricks(b).len()
     }
 
+    /// A brick on the ground never falls, whatever other bricks are removed
+    fn is_on_ground(&self, b: &Brick) -> bool {
+        b.bottom_z() == GROUND_Z
+    }
+
+    /// Bricks directly holding up the given brick. Empty for bricks
+    /// on the ground.
     fn below_bricks(&self, b: &Brick) -> Vec<&Brick> {
+        if self.is_on_ground(b) {
+            return Vec::new();
+        }
+
         if let Some(v) = self.by_top_z.get(&(b.bottom_z() - 1)) {
             v.iter()
                 .map(|i| self.brick_with_index(*i))
@@ -208,6 +246,14 @@ impl Building {
         }
     }
 
+    /// A brick can be disintegrated if every brick it holds up is also
+    /// held up by some other brick.
+    fn can_disintegrate(&self, b: &Brick) -> bool {
+        self.above_bricks(b)
+            .iter()
+            .all(|above| self.holding_up(above) > 1)
+    }
+
     fn fall_count_if_removed(&self, b: &Brick) -> usize {
         // Figure out how many bricks would fall if this brick were removed
         let mut removed = HashSet::new();
@@ -222,7 +268,8 @@ impl Building {
 
             // Check every brick above b
             for other in self.bricks.iter().filter(|o| o.bottom_z() == b.top_z() + 1) {
-                if self.below_bricks(other).iter().all(|x| removed.contains(x))
+                if !self.is_on_ground(other)
+                    && self.below_bricks(other).iter().all(|x| removed.contains(x))
                     && !removed.contains(other)
                 {
                     process.push_back(other);
@@ -261,67 +308,114 @@ impl Building {
 
         deps
     }
+
+    /// GraphViz representation of [Building::layout_graph]
+    pub fn dot(&self) -> String {
+        let g = self.layout_graph();
+        format!(
+            "{:?}",
+            Dot::with_attr_getters(
+                &g,
+                &[Config::EdgeNoLabel],
+                &|g, e| format!("label = \"{} holds {}\"", g[e.source()], g[e.target()]),
+                &|_, _| String::new(),
+            )
+        )
+    }
 }
 
-fn vec3d(s: &str) -> IResult<&str, IVec3> {
+fn vec3d(s: &str) -> IResult<&str, I64Vec3> {
     tuple((
-        nom::character::complete::i32.terminated(tag(",")),
-        nom::character::complete::i32.terminated(tag(",")),
-        nom::character::complete::i32,
+        nom::character::complete::i64.terminated(tag(",")),
+        nom::character::complete::i64.terminated(tag(",")),
+        nom::character::complete::i64,
     ))
-    .map(|(x, y, z)| IVec3::new(x, y, z))
+    .map(|(x, y, z)| I64Vec3::new(x, y, z))
     .parse(s)
 }
 
-fn line(s: &str) -> IResult<&str, (IVec3, IVec3)> {
+fn line(s: &str) -> IResult<&str, (I64Vec3, I64Vec3)> {
     separated_pair(vec3d, tag("~"), vec3d).parse(s)
 }
 
 pub fn parse_input(s: &str) -> Vec<Brick> {
-    let (r, i) = separated_list1(
-        line_ending,
-        line.map(|(start, end)| Brick { start, end, idx: 0 }),
-    )
-    .parse(s)
-    .expect("Valid input");
-    assert_eq!(r, "");
+    try_parse_input(s).expect("Valid input")
+}
 
-    i.iter()
+/// Same as [parse_input], however reports the first malformed line
+/// instead of panicking.
+pub fn try_parse_input(s: &str) -> Result<Vec<Brick>, String> {
+    s.lines()
         .enumerate()
-        .map(|(idx, b)| Brick { idx, ..*b })
+        .map(|(idx, l)| match line(l) {
+            Ok(("", (start, end))) => Ok(Brick { start, end, idx }),
+            _ => Err(format!("Invalid brick on line {}: {:?}", idx + 1, l)),
+        })
         .collect()
 }
 
 pub fn part1(input: &str) -> usize {
-    let input = parse_input(input);
-    let building = Building::new(input);
+    try_part1(input).expect("Valid input")
+}
 
-    // let g = building.layout_graph();
-    // println!("{:?}", Dot::with_config(&g, &[Config::EdgeNoLabel]));
+/// Same as [part1], returning an error on malformed input
+pub fn try_part1(input: &str) -> Result<usize, String> {
+    let input = try_parse_input(input)?;
+    let building = Building::new(input);
 
-    building
+    Ok(building
         .bricks
         .iter()
-        .filter(|brick| {
-            building
-                .above_bricks(brick)
-                .iter()
-                .all(|above| building.holding_up(above) > 1)
-        })
-        .count()
+        .filter(|brick| building.can_disintegrate(brick))
+        .count())
 }
 
 pub fn part2(input: &str) -> usize {
+    try_part2(input).expect("Valid input")
+}
+
+/// Same as [part2], returning an error on malformed input
+pub fn try_part2(input: &str) -> Result<usize, String> {
+    let input = try_parse_input(input)?;
+    let building = Building::new(input);
+
+    Ok(building
+        .bricks
+        .iter()
+        .map(|b| building.fall_count_if_removed(b))
+        .sum())
+}
+
+/// Same as [part2], however computes the falls for every removed brick
+/// in parallel
+pub fn par_part2(input: &str) -> usize {
     let input = parse_input(input);
     let building = Building::new(input);
 
     building
         .bricks
-        .iter()
+        .par_iter()
         .map(|b| building.fall_count_if_removed(b))
         .sum()
 }
 
+/// Computes both the [part1] and [part2] answers, settling the bricks only once.
+///
+/// Returns the number of bricks that can be disintegrated and the total number
+/// of bricks falling over all chain reactions.
+pub fn analyze(input: &str) -> (usize, usize) {
+    let building = Building::new(parse_input(input));
+
+    building
+        .bricks
+        .iter()
+        .fold((0, 0), |(disintegrable, falls), b| {
+            let fall_count = building.fall_count_if_removed(b);
+            let safe = usize::from(building.can_disintegrate(b));
+            (disintegrable + safe, falls + fall_count)
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -329,17 +423,85 @@ mod tests {
     #[test]
     fn test_brick_intersect() {
         assert!(!Brick {
-            start: IVec3::new(0, 0, 4),
-            end: IVec3::new(0, 2, 4),
+            start: I64Vec3::new(0, 0, 4),
+            end: I64Vec3::new(0, 2, 4),
             idx: 0,
         }
         .intesects_xy(&Brick {
-            start: IVec3::new(2, 0, 5),
-            end: IVec3::new(2, 2, 5),
+            start: I64Vec3::new(2, 0, 5),
+            end: I64Vec3::new(2, 2, 5),
             idx: 0,
         }));
     }
 
+    #[test]
+    fn test_settled() {
+        let building = Building::new(parse_input(include_str!("../example.txt")));
+
+        assert_eq!(building.settled_bricks().len(), 7);
+        assert_eq!(
+            building.settled_bricks().iter().map(|b| b.top_z()).max(),
+            Some(6)
+        );
+
+        let heights = building.heights();
+        assert_eq!(heights.values().max(), Some(&6));
+        assert_eq!(heights.len(), 9);
+        assert_eq!(heights.get(&(1, 1)), Some(&6));
+        assert_eq!(heights.get(&(0, 0)), Some(&3));
+        assert_eq!(heights.get(&(0, 1)), Some(&4));
+    }
+
+    #[test]
+    fn test_high_bricks() {
+        // dropping these one step at a time would take forever
+        let building = Building::new(parse_input(
+            "0,0,10000~0,0,10001\n0,0,5000000000~1,0,5000000000\n1,0,9000000000~1,0,9000000000",
+        ));
+
+        let bricks = building.settled_bricks();
+        assert_eq!(
+            bricks
+                .iter()
+                .map(|b| (b.bottom_z(), b.top_z()))
+                .collect::<Vec<_>>(),
+            [(1, 2), (3, 3), (4, 4)]
+        );
+    }
+
+    #[test]
+    fn test_ground_bricks() {
+        // A and B on the ground, C resting on both
+        let building = Building::new(parse_input("0,0,1~0,0,1\n1,0,1~1,0,1\n0,0,2~1,0,2"));
+        let bricks = building.settled_bricks();
+
+        assert!(building.is_on_ground(&bricks[0]));
+        assert!(building.is_on_ground(&bricks[1]));
+        assert!(!building.is_on_ground(&bricks[2]));
+        assert!(building.below_bricks(&bricks[0]).is_empty());
+        assert_eq!(building.holding_up(&bricks[2]), 2);
+
+        // removing the top brick does not make the ground bricks fall
+        assert_eq!(building.fall_count_if_removed(&bricks[2]), 0);
+
+        // neither does removing a ground brick: C is still held up by the other
+        assert_eq!(building.fall_count_if_removed(&bricks[0]), 0);
+        assert_eq!(building.fall_count_if_removed(&bricks[1]), 0);
+    }
+
+    #[test]
+    fn test_dot() {
+        let building = Building::new(parse_input(include_str!("../example.txt")));
+        let dot = building.dot();
+
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains("label = \"A holds B\""));
+        assert!(dot.contains("label = \"A holds C\""));
+        assert!(dot.contains("label = \"F holds G\""));
+        assert!(!dot.contains("label = \"B holds A\""));
+        assert_eq!(dot.matches(" holds ").count(), 9);
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(part1(include_str!("../example.txt")), 5);
@@ -349,4 +511,37 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(include_str!("../example.txt")), 7);
     }
+
+    #[test]
+    fn test_par_part2() {
+        assert_eq!(par_part2(include_str!("../example.txt")), 7);
+        assert_eq!(
+            par_part2(include_str!("../example.txt")),
+            part2(include_str!("../example.txt"))
+        );
+    }
+
+    #[test]
+    fn test_try_parse_input() {
+        assert_eq!(
+            try_parse_input(include_str!("../example.txt")).map(|b| b.len()),
+            Ok(7)
+        );
+
+        assert_eq!(
+            try_parse_input("1,0,1~1,2,1\n0,0,2~2,0\n0,2,3~2,2,3"),
+            Err("Invalid brick on line 2: \"0,0,2~2,0\"".into())
+        );
+        assert_eq!(
+            try_part1("1,0,1~1,2,1\n0,0,2~2,0,2 extra"),
+            Err("Invalid brick on line 2: \"0,0,2~2,0,2 extra\"".into())
+        );
+        assert!(try_part2("1,0,1").is_err());
+        assert_eq!(try_part2(include_str!("../example.txt")), Ok(7));
+    }
+
+    #[test]
+    fn test_analyze() {
+        assert_eq!(analyze(include_str!("../example.txt")), (5, 7));
+    }
 }
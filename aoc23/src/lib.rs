@@ -1,7 +1,10 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+};
 
-use pathfinding::directed::dijkstra::dijkstra;
-use tracing::{info, instrument, trace};
+use grid::parse_char_grid;
+use tracing::{info, trace};
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 enum Direction {
@@ -95,58 +98,15 @@ struct Input {
     cols: usize,
 }
 
-struct JunctionGraph {
-    distances: HashMap<Point, Vec<(Point, usize)>>,
-}
-
-impl JunctionGraph {
-    fn max_distance(&self, start: Point, end: Point) -> usize {
-        // Terrible algorighm, however since few junctions maybe it works
-        // on these maps ...
-        self.max_distance_rec(start, 0, end, &mut HashSet::new())
-    }
-
-    #[instrument(skip_all)]
-    fn max_distance_rec(
-        &self,
-        start: Point,
-        so_far: usize,
-        end: Point,
-        visited: &mut HashSet<Point>,
-    ) -> usize {
-        trace!("{:?} distance {}", start, so_far);
-        let neighbours = match self.distances.get(&start) {
-            Some(v) => v,
-            None => return 0,
-        };
-
-        let mut m = so_far;
-
-        for (n, d) in neighbours.iter().filter(|(n, _)| !visited.contains(n)).collect::<Vec<_>>() {
-            if *n == end {
-                m = m.max(so_far + d)
-            } else {
-                visited.insert(*n);
-                m = m.max(self.max_distance_rec(*n, so_far + d, end, visited));
-                visited.remove(n);
-            }
-        }
-        m
-    }
-}
-
 impl Input {
     fn parse(input: &str) -> Self {
-        let mut data = HashMap::with_capacity(input.len());
-        let mut rows = 0;
-        let mut cols = 0;
-
-        for (row, l) in input.split("\n").enumerate() {
-            rows += 1;
-            cols = l.len();
-            for (col, c) in l.chars().enumerate() {
-                data.insert(
-                    (row as i32, col as i32).into(),
+        let (chars, rows, cols) = parse_char_grid(input);
+
+        let data = chars
+            .into_iter()
+            .map(|(pos, c)| {
+                (
+                    pos.into(),
                     match c {
                         '.' => Cell::Empty,
                         '#' => Cell::Wall,
@@ -156,9 +116,10 @@ impl Input {
                         '<' => Cell::Slope(Direction::West),
                         _ => panic!("invalid input: {}", c),
                     },
-                );
-            }
-        }
+                )
+            })
+            .collect();
+
         Self { data, rows, cols }
     }
 
@@ -219,7 +180,9 @@ impl Input {
         cnt > 2
     }
 
-    fn longest_path(&self, start: Point, end: Point) -> usize {
+    /// Direct routes (i.e. not passing through other junctions) between
+    /// start, end and any junction. Routes include both their ends.
+    fn junction_routes(&self, start: Point, end: Point) -> Routes {
         // Nodes are start, end and any junction
         let mut junctions = self
             .data
@@ -238,13 +201,13 @@ impl Input {
         junctions.insert(end);
         info!("Junctions: {:?}", junctions);
 
-        let mut distances: HashMap<Point, Vec<(Point, usize)>> = HashMap::new();
+        let mut routes = Routes::new();
 
         // assume this is a graph. Figure out the length of a DIRECT path from each
         // junction to another junction
         for a in junctions.iter() {
             for b in junctions.iter().filter(|x| *x != a) {
-                if let Some(r) = dijkstra(
+                if let Some((cost, route)) = search::dijkstra_path(
                     a,
                     |x| {
                         Direction::all()
@@ -257,41 +220,178 @@ impl Input {
                     },
                     |p| p == b,
                 ) {
-                    trace!("TODO: path from {:?} to {:?} == {}", a, b, r.1);
-                    match distances.get_mut(a) {
-                        Some(v) => v.push((*b, r.1)),
+                    trace!("TODO: path from {:?} to {:?} == {}", a, b, cost);
+                    match routes.get_mut(a) {
+                        Some(v) => v.push((*b, route)),
                         None => {
-                            distances.insert(*a, vec![(*b, r.1)]);
+                            routes.insert(*a, vec![(*b, route)]);
                         }
                     }
                 }
             }
         }
-        
-        let g = JunctionGraph {
-            distances,
+
+        routes
+    }
+
+    fn longest_path(&self, start: Point, end: Point) -> usize {
+        let distances = route_distances(&self.junction_routes(start, end));
+
+        // Exhaustive search, however since there are few junctions it works
+        // on these maps ...
+        search::longest_simple_path(&distances, start, end).expect("end is reachable")
+    }
+
+    /// Longest path length and the number of search states visited to find it
+    fn longest_path_with(&self, start: Point, end: Point, mode: SearchMode) -> (usize, usize) {
+        let distances = route_distances(&self.junction_routes(start, end));
+
+        let (longest, states) = match mode {
+            SearchMode::Exact => search::longest_simple_path_counted(&distances, start, end),
+            SearchMode::Pruned => search::longest_simple_path_pruned(&distances, start, end),
         };
 
-        g.max_distance(start, end)
+        (longest.expect("end is reachable"), states)
+    }
+
+    /// All the cells of the longest path, starting with `start` and ending with `end`
+    fn longest_route(&self, start: Point, end: Point, mode: SearchMode) -> Vec<Point> {
+        let routes = self.junction_routes(start, end);
+        let distances = route_distances(&routes);
+
+        let (_, junctions) = match mode {
+            SearchMode::Exact => search::longest_simple_route(&distances, start, end),
+            SearchMode::Pruned => search::longest_simple_route_pruned(&distances, start, end),
+        }
+        .expect("end is reachable");
+
+        let mut result = vec![start];
+        for (a, b) in junctions.iter().zip(junctions.iter().skip(1)) {
+            let (_, route) = routes[a]
+                .iter()
+                .find(|(target, _)| target == b)
+                .expect("junctions are connected");
+            result.extend(route.iter().skip(1));
+        }
+        result
+    }
+
+    fn render(&self, path: &HashSet<Point>, start: Point) -> String {
+        let mut result = String::with_capacity((self.cols + 1) * self.rows);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let p = (row as i32, col as i32).into();
+                result.push(if p == start {
+                    'S'
+                } else if path.contains(&p) {
+                    'O'
+                } else {
+                    match self.data.get(&p) {
+                        Some(Cell::Empty) => '.',
+                        Some(Cell::Wall) => '#',
+                        Some(Cell::Slope(Direction::North)) => '^',
+                        Some(Cell::Slope(Direction::East)) => '>',
+                        Some(Cell::Slope(Direction::South)) => 'v',
+                        Some(Cell::Slope(Direction::West)) => '<',
+                        None => ' ',
+                    }
+                });
+            }
+            result.push('\n');
+        }
+        result
     }
 }
 
-pub fn part1(input: &str) -> usize {
-    let input = Input::parse(input);
+/// How to search for the longest hike
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum SearchMode {
+    /// Try every possible path
+    Exact,
+    /// Skip paths that cannot beat the best path found so far, based on an
+    /// optimistic bound of the remaining distance. Still returns the longest
+    /// path.
+    Pruned,
+}
+
+type Routes = HashMap<Point, Vec<(Point, Vec<Point>)>>;
+
+/// Number of steps for each junction route.
+///
+/// Longer routes are listed first, so searches find a good path early.
+fn route_distances(routes: &Routes) -> HashMap<Point, Vec<(Point, usize)>> {
+    routes
+        .iter()
+        .map(|(a, r)| {
+            let mut distances: Vec<_> = r.iter().map(|(b, route)| (*b, route.len() - 1)).collect();
+            distances.sort_by_key(|(b, d)| (Reverse(*d), *b));
+            (*a, distances)
+        })
+        .collect()
+}
+
+/// Longest hike from the top-left to the bottom-right opening.
+///
+/// When `respect_slopes` is false, slopes are walked like regular paths.
+pub fn longest_hike(input: &str, respect_slopes: bool) -> usize {
+    let input = hike_map(input, respect_slopes);
     input.longest_path(
         (0, 1).into(),
         ((input.rows - 1) as i32, (input.cols - 2) as i32).into(),
     )
 }
 
-pub fn part2(input: &str) -> usize {
-    let input = Input::parse(input).no_slopes();
-    input.longest_path(
+/// Same as [longest_hike], using the given search mode. Also returns the
+/// number of search states visited.
+pub fn longest_hike_with(input: &str, respect_slopes: bool, mode: SearchMode) -> (usize, usize) {
+    let input = hike_map(input, respect_slopes);
+    input.longest_path_with(
         (0, 1).into(),
         ((input.rows - 1) as i32, (input.cols - 2) as i32).into(),
+        mode,
     )
 }
 
+fn hike_map(input: &str, respect_slopes: bool) -> Input {
+    let input = Input::parse(input);
+    if respect_slopes {
+        input
+    } else {
+        input.no_slopes()
+    }
+}
+
+/// Draws the longest hike over the map: `S` is the start and every step
+/// taken is marked with `O`.
+///
+/// When `respect_slopes` is false, slopes are walked like regular paths.
+pub fn render_path(input: &str, respect_slopes: bool) -> String {
+    render_path_with(input, respect_slopes, SearchMode::Pruned)
+}
+
+/// Same as [render_path], using the given search mode to find the hike.
+pub fn render_path_with(input: &str, respect_slopes: bool, mode: SearchMode) -> String {
+    let map = Input::parse(input);
+    let start = (0, 1).into();
+    let end = ((map.rows - 1) as i32, (map.cols - 2) as i32).into();
+
+    let route = if respect_slopes {
+        map.longest_route(start, end, mode)
+    } else {
+        map.no_slopes().longest_route(start, end, mode)
+    };
+
+    map.render(&route.into_iter().collect(), start)
+}
+
+pub fn part1(input: &str) -> usize {
+    longest_hike(input, true)
+}
+
+pub fn part2(input: &str) -> usize {
+    longest_hike(input, false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -305,4 +405,53 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(include_str!("../example.txt")), 154);
     }
+
+    #[test]
+    fn test_longest_hike() {
+        assert_eq!(longest_hike(include_str!("../example.txt"), true), 94);
+        assert_eq!(longest_hike(include_str!("../example.txt"), false), 154);
+    }
+
+    #[test]
+    fn test_search_modes() {
+        let example = include_str!("../example.txt");
+
+        let (exact, exact_states) = longest_hike_with(example, false, SearchMode::Exact);
+        let (pruned, pruned_states) = longest_hike_with(example, false, SearchMode::Pruned);
+
+        assert_eq!(exact, 154);
+        assert_eq!(pruned, 154);
+        assert!(
+            pruned_states < exact_states,
+            "pruned: {}, exact: {}",
+            pruned_states,
+            exact_states
+        );
+
+        assert_eq!(longest_hike_with(example, true, SearchMode::Pruned).0, 94);
+    }
+
+    #[test]
+    fn test_render_path() {
+        let example = include_str!("../example.txt");
+
+        let rendered = render_path(example, true);
+        assert_eq!(rendered.chars().filter(|c| *c == 'O').count(), 94);
+        assert_eq!(rendered.chars().filter(|c| *c == 'S').count(), 1);
+
+        // the path only walks over non-wall cells, the rest of the map is unchanged
+        assert_eq!(rendered.lines().count(), example.lines().count());
+        for (expected, actual) in example.lines().zip(rendered.lines()) {
+            assert_eq!(expected.len(), actual.len());
+            for (e, a) in expected.chars().zip(actual.chars()) {
+                assert!(a == e || (e != '#' && (a == 'O' || a == 'S')));
+            }
+        }
+
+        let rendered = render_path(example, false);
+        assert_eq!(rendered.chars().filter(|c| *c == 'O').count(), 154);
+
+        let rendered = render_path_with(example, false, SearchMode::Exact);
+        assert_eq!(rendered.chars().filter(|c| *c == 'O').count(), 154);
+    }
 }
@@ -9,7 +9,23 @@ fn main() {
 fn part1() {
     aoc24::part1(
         black_box(include_str!("../input.txt")),
-        (200000000000000_f32, 400000000000000_f32),
+        (200000000000000_f64, 400000000000000_f64),
+    );
+}
+
+#[divan::bench]
+fn par_part1() {
+    aoc24::par_part1(
+        black_box(include_str!("../input.txt")),
+        (200000000000000_f64, 400000000000000_f64),
+    );
+}
+
+#[divan::bench]
+fn part1_exact() {
+    aoc24::part1_exact(
+        black_box(include_str!("../input.txt")),
+        (200000000000000, 400000000000000),
     );
 }
 
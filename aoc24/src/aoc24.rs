@@ -16,12 +16,9 @@ fn main() {
         .with(EnvFilter::from_default_env())
         .init();
 
-    let s1 = aoc24::part1(
+    runner::run(
         include_str!("../input.txt"),
-        (200000000000000_f32, 400000000000000_f32),
+        |s| aoc24::part1_exact(s, (200000000000000, 400000000000000)),
+        aoc24::part2,
     );
-    println!("Part 1: {}", s1);
-
-    let s2 = aoc24::part2(include_str!("../input.txt"));
-    println!("Part 2: {}", s2);
 }
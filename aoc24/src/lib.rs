@@ -1,12 +1,27 @@
 use std::fmt::Debug;
 
-use glam::{Mat2, Vec2, Vec3};
+use glam::{DMat2, DVec2, DVec3, I64Vec3};
+use rayon::prelude::*;
 use tracing::{info, instrument};
 
 #[derive(PartialEq, Copy, Clone)]
 struct Hailstone {
-    start: Vec3,
-    direction: Vec3,
+    start: DVec3,
+    direction: DVec3,
+
+    // same as start/direction, without any precision loss
+    exact_start: (i64, i64, i64),
+    exact_direction: (i64, i64, i64),
+}
+
+/// Integer 3d vector, used for exact positions and velocities
+pub type Vec3i = I64Vec3;
+
+/// A thrown rock, moving by `direction` every nanosecond
+#[derive(Debug, PartialEq, Copy, Clone)]
+struct Rock {
+    start: Vec3i,
+    direction: Vec3i,
 }
 
 impl Debug for Hailstone {
@@ -24,7 +39,7 @@ impl Debug for Hailstone {
 }
 
 mod parse {
-    use glam::Vec3;
+    use glam::DVec3;
     use nom::{
         bytes::complete::tag,
         character::complete::{line_ending, space0},
@@ -36,19 +51,27 @@ mod parse {
 
     use crate::Hailstone;
 
-    fn vector(input: &str) -> IResult<&str, Vec3> {
+    fn vector(input: &str) -> IResult<&str, (i64, i64, i64)> {
         tuple((
             nom::character::complete::i64,
             nom::character::complete::i64.preceded_by(tuple((space0, tag(","), space0))),
             nom::character::complete::i64.preceded_by(tuple((space0, tag(","), space0))),
         ))
-        .map(|(x, y, z)| Vec3::new(x as f32, y as f32, z as f32))
         .parse(input)
     }
 
+    fn as_vec3((x, y, z): (i64, i64, i64)) -> DVec3 {
+        DVec3::new(x as f64, y as f64, z as f64)
+    }
+
     pub fn hailstone(input: &str) -> IResult<&str, Hailstone> {
         separated_pair(vector, tuple((space0, tag("@"), space0)), vector)
-            .map(|(start, direction)| Hailstone { start, direction })
+            .map(|(start, direction)| Hailstone {
+                start: as_vec3(start),
+                direction: as_vec3(direction),
+                exact_start: start,
+                exact_direction: direction,
+            })
             .parse(input)
     }
 
@@ -64,15 +87,15 @@ mod parse {
 
 impl Hailstone {
     #[instrument(skip_all)]
-    fn intersect_2d(&self, other: &Hailstone) -> Option<Vec2> {
+    fn intersect_2d(&self, other: &Hailstone) -> Option<DVec2> {
         // Look at 2d only
-        let s1 = Vec2::new(self.start.x, self.start.y);
-        let d1 = Vec2::new(self.direction.x, self.direction.y);
+        let s1 = DVec2::new(self.start.x, self.start.y);
+        let d1 = DVec2::new(self.direction.x, self.direction.y);
 
-        let s2 = Vec2::new(other.start.x, other.start.y);
-        let d2 = Vec2::new(other.direction.x, other.direction.y);
+        let s2 = DVec2::new(other.start.x, other.start.y);
+        let d2 = DVec2::new(other.direction.x, other.direction.y);
 
-        let m = Mat2::from_cols(d1, -d2);
+        let m = DMat2::from_cols(d1, -d2);
 
         if m.determinant() == 0.0 {
             return None;
@@ -89,9 +112,74 @@ impl Hailstone {
         //  t.y*d2 + s2
         Some(t.x * d1 + s1)
     }
+
+    /// Checks if the intersection with the other hailstone (ignoring z)
+    /// is in the future and inside the given box, edges included.
+    fn intersects_in_box(&self, other: &Hailstone, range: (f64, f64)) -> bool {
+        let Some(i) = self.intersect_2d(other) else {
+            return false;
+        };
+
+        // intersections are not exact: points within a few rounding errors
+        // of an edge are decided on integers if the box allows it
+        let tolerance = 16.0 * f64::EPSILON * range.0.abs().max(range.1.abs()).max(1.0);
+        let near_edge =
+            |v: f64| (v - range.0).abs() <= tolerance || (v - range.1).abs() <= tolerance;
+
+        if (near_edge(i.x) || near_edge(i.y)) && range.0.fract() == 0.0 && range.1.fract() == 0.0 {
+            return self.intersects_in_box_exact(other, range.0 as i64, range.1 as i64);
+        }
+
+        let inside = |v: f64| v >= range.0 - tolerance && v <= range.1 + tolerance;
+        inside(i.x) && inside(i.y)
+    }
+
+    /// Same as [Hailstone::intersect_2d] followed by a check that the
+    /// intersection is inside the `lo..=hi` box, however computed on integers
+    /// only (no precision loss).
+    fn intersects_in_box_exact(&self, other: &Hailstone, lo: i64, hi: i64) -> bool {
+        let (s1x, s1y, _) = self.exact_start;
+        let (d1x, d1y, _) = self.exact_direction;
+        let (s2x, s2y, _) = other.exact_start;
+        let (d2x, d2y, _) = other.exact_direction;
+
+        let cross =
+            |ax: i64, ay: i64, bx: i64, by: i64| ax as i128 * by as i128 - ay as i128 * bx as i128;
+
+        // s1 + t * d1 == s2 + u * d2, with
+        //   t = cross(s2 - s1, d2) / cross(d1, d2)
+        //   u = cross(s2 - s1, d1) / cross(d1, d2)
+        let mut denom = cross(d1x, d1y, d2x, d2y);
+        if denom == 0 {
+            // parallel
+            return false;
+        }
+        let mut t = cross(s2x - s1x, s2y - s1y, d2x, d2y);
+        let mut u = cross(s2x - s1x, s2y - s1y, d1x, d1y);
+
+        // keep the denominator positive so comparisons do not flip
+        if denom < 0 {
+            denom = -denom;
+            t = -t;
+            u = -u;
+        }
+
+        if t < 0 || u < 0 {
+            // interesect in the past
+            return false;
+        }
+
+        // intersection is s1 + (t/denom) * d1, compared as multiples of denom
+        let in_box = |s: i64, d: i64| {
+            let p = s as i128 * denom + t * d as i128;
+            (lo as i128 * denom..=hi as i128 * denom).contains(&p)
+        };
+
+        in_box(s1x, d1x) && in_box(s1y, d1y)
+    }
 }
 
-pub fn part1(input: &str, range: (f32, f32)) -> usize {
+pub fn part1(input: &str, range: (f64, f64)) -> usize {
     let stones = parse::input(input);
 
     info!("Stones: {}", stones.len());
@@ -100,10 +188,8 @@ pub fn part1(input: &str, range: (f32, f32)) -> usize {
 
     for (idx, a) in stones.iter().enumerate() {
         for b in stones.iter().skip(idx + 1) {
-            if let Some(i) = a.intersect_2d(b) {
-                if i.x >= range.0 && i.x <= range.1 && i.y >= range.0 && i.y <= range.1 {
-                    cnt += 1;
-                }
+            if a.intersects_in_box(b, range) {
+                cnt += 1;
             }
         }
     }
@@ -111,20 +197,170 @@ pub fn part1(input: &str, range: (f32, f32)) -> usize {
     cnt
 }
 
-pub fn part2(input: &str) -> usize {
+/// Same as [part1], however splits the work across threads
+pub fn par_part1(input: &str, range: (f64, f64)) -> usize {
     let stones = parse::input(input);
-    
-    // steps:
-    //  - any 3 lines (except parallel!) should uniquely identify the line direction
-    //  - closest intersection point determines the position (by time)
-    
-    let a = stones.get(0).expect("has sufficient data (1)");
-    let b = stones.get(1).expect("has sufficient data (2)");
-    let c = stones.get(2).expect("has sufficient data (3)");
-    
-    
-    // TODO: implement
-    0
+
+    info!("Stones: {}", stones.len());
+
+    (0..stones.len())
+        .into_par_iter()
+        .map(|idx| {
+            let a = &stones[idx];
+            stones
+                .iter()
+                .skip(idx + 1)
+                .filter(|b| a.intersects_in_box(b, range))
+                .count()
+        })
+        .sum()
+}
+
+/// Same as [part1], however computed exactly on integers.
+pub fn part1_exact(input: &str, range: (i64, i64)) -> usize {
+    let stones = parse::input(input);
+
+    let mut cnt = 0;
+
+    for (idx, a) in stones.iter().enumerate() {
+        for b in stones.iter().skip(idx + 1) {
+            if a.intersects_in_box_exact(b, range.0, range.1) {
+                cnt += 1;
+            }
+        }
+    }
+
+    cnt
+}
+
+impl Hailstone {
+    fn exact_start(&self) -> Vec3i {
+        self.exact_start.into()
+    }
+
+    fn exact_direction(&self) -> Vec3i {
+        self.exact_direction.into()
+    }
+}
+
+/// Time at which the path `start + t * direction` crosses the plane through
+/// the origin with the given normal, if it is an integer.
+fn plane_crossing_time(start: Vec3i, direction: Vec3i, normal: [i128; 3]) -> Option<i128> {
+    let dot =
+        |v: Vec3i| v.x as i128 * normal[0] + v.y as i128 * normal[1] + v.z as i128 * normal[2];
+    let num = -dot(start);
+    let den = dot(direction);
+
+    if den == 0 || num % den != 0 {
+        return None;
+    }
+    Some(num / den)
+}
+
+fn cross(a: Vec3i, b: Vec3i) -> [i128; 3] {
+    let (ax, ay, az) = (a.x as i128, a.y as i128, a.z as i128);
+    let (bx, by, bz) = (b.x as i128, b.y as i128, b.z as i128);
+    [ay * bz - az * by, az * bx - ax * bz, ax * by - ay * bx]
+}
+
+impl Rock {
+    /// Checks if the rock hits the hailstone at some integer time >= 0
+    fn rock_hits(&self, hs: &Hailstone) -> bool {
+        // per axis: rock.start - hs.start == t * (hs.direction - rock.direction)
+        let dp = (self.start - hs.exact_start()).to_array();
+        let dv = (hs.exact_direction() - self.direction).to_array();
+
+        let mut time = None;
+        for (p, v) in dp.into_iter().zip(dv) {
+            if v == 0 {
+                if p != 0 {
+                    return false;
+                }
+                continue;
+            }
+            if p % v != 0 {
+                return false;
+            }
+            match time {
+                None => time = Some(p / v),
+                Some(t) if t == p / v => (),
+                Some(_) => return false,
+            }
+        }
+
+        time.unwrap_or(0) >= 0
+    }
+
+    /// Finds the rock that hits `stones[0]`, `a` and `b`.
+    ///
+    /// Works relative to `stones[0]`: the rock must then pass through the origin,
+    /// so it lies on the plane containing the origin and `a`'s path. Crossing
+    /// that plane gives the collision time with `b` (and vice-versa for `a`).
+    fn through(origin: &Hailstone, a: &Hailstone, b: &Hailstone) -> Option<Rock> {
+        let relative = |h: &Hailstone| {
+            (
+                h.exact_start() - origin.exact_start(),
+                h.exact_direction() - origin.exact_direction(),
+            )
+        };
+        let (sa, da) = relative(a);
+        let (sb, db) = relative(b);
+
+        let ta = plane_crossing_time(sa, da, cross(sb, db))?;
+        let tb = plane_crossing_time(sb, db, cross(sa, da))?;
+
+        if ta == tb {
+            return None;
+        }
+
+        let ta = i64::try_from(ta).ok()?;
+        let tb = i64::try_from(tb).ok()?;
+
+        // actual collision points
+        let pa = a.exact_start() + a.exact_direction() * ta;
+        let pb = b.exact_start() + b.exact_direction() * tb;
+
+        let delta = pb - pa;
+        let dt = tb - ta;
+        if delta % dt != Vec3i::ZERO {
+            return None;
+        }
+
+        let direction = delta / dt;
+        Some(Rock {
+            start: pa - direction * ta,
+            direction,
+        })
+    }
+}
+
+/// Start position and velocity of the rock that hits every hailstone
+pub fn throw_rock(input: &str) -> (Vec3i, Vec3i) {
+    let stones = parse::input(input);
+
+    let (origin, rest) = stones.split_first().expect("has hailstones");
+
+    // any pair works unless parallel/degenerate to the origin hailstone. Try until
+    // one of them finds an answer.
+    let rock = rest
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, a)| rest.iter().skip(idx + 1).map(move |b| (a, b)))
+        .find_map(|(a, b)| Rock::through(origin, a, b))
+        .expect("a rock can be thrown");
+
+    info!("Rock: {:?}", rock);
+    assert!(
+        stones.iter().all(|hs| rock.rock_hits(hs)),
+        "rock should hit every hailstone"
+    );
+
+    (rock.start, rock.direction)
+}
+
+pub fn part2(input: &str) -> i64 {
+    let (start, _) = throw_rock(input);
+    start.x + start.y + start.z
 }
 
 #[cfg(test)]
@@ -142,17 +378,109 @@ mod tests {
 
         assert_eq!(
             a.intersect_2d(&b).expect("intersection"),
-            Vec2::new(-6.0, -5.0),
+            DVec2::new(-6.0, -5.0),
         );
         assert_eq!(
             b.intersect_2d(&a).expect("intersection"),
-            Vec2::new(-6.0, -5.0),
+            DVec2::new(-6.0, -5.0),
         );
     }
 
     #[test_log::test]
     fn test_part1() {
-        assert_eq!(part1(include_str!("../example.txt"), (7_f32, 27_f32)), 2);
+        assert_eq!(part1(include_str!("../example.txt"), (7_f64, 27_f64)), 2);
+    }
+
+    #[test_log::test]
+    fn test_par_part1() {
+        assert_eq!(
+            par_part1(include_str!("../example.txt"), (7_f64, 27_f64)),
+            part1(include_str!("../example.txt"), (7_f64, 27_f64))
+        );
+    }
+
+    #[test_log::test]
+    fn test_part1_exact() {
+        assert_eq!(part1_exact(include_str!("../example.txt"), (7, 27)), 2);
+
+        let stones = parse::input(include_str!("../example.txt"));
+        for (idx, a) in stones.iter().enumerate() {
+            for b in stones.iter().skip(idx + 1) {
+                assert_eq!(
+                    a.intersects_in_box_exact(b, 7, 27),
+                    a.intersects_in_box(b, (7.0, 27.0)),
+                    "{:?} and {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test_log::test]
+    fn test_part1_box_edge() {
+        // paths cross at (3, 1): x on the upper edge, y on the lower edge
+        let input = "0, 0, 0 @ 3, 1, 0\n7, 0, 0 @ -4, 1, 0";
+
+        assert_eq!(part1(input, (1.0, 3.0)), 1);
+        assert_eq!(par_part1(input, (1.0, 3.0)), 1);
+        assert_eq!(part1_exact(input, (1, 3)), 1);
+
+        assert_eq!(part1(input, (1.5, 3.0)), 0);
+        assert_eq!(part1(input, (1.0, 2.5)), 0);
+
+        // paths crossing at x = 2e14 + offset, right next to the edge of a
+        // real-input sized box
+        let range = (200000000000000.0, 400000000000000.0);
+        for (offset, expected) in [(-1, 0), (0, 1), (1, 1)] {
+            let x = 200000000000000_i64 + offset;
+            let input = format!(
+                "{}, 300000000000000, 0 @ 1, 0, 0\n{}, 299999999999990, 0 @ 0, 1, 0",
+                x - 10,
+                x
+            );
+            assert_eq!(part1(&input, range), expected, "offset {}", offset);
+            assert_eq!(
+                part1_exact(&input, (200000000000000, 400000000000000)),
+                expected,
+                "offset {}",
+                offset
+            );
+        }
+    }
+
+    #[test_log::test]
+    fn test_throw_rock() {
+        assert_eq!(
+            throw_rock(include_str!("../example.txt")),
+            (Vec3i::new(24, 13, 10), Vec3i::new(-3, 1, 2))
+        );
+    }
+
+    #[test_log::test]
+    fn test_rock_hits() {
+        let rock = Rock {
+            start: Vec3i::new(24, 13, 10),
+            direction: Vec3i::new(-3, 1, 2),
+        };
+        for hs in parse::input(include_str!("../example.txt")) {
+            assert!(rock.rock_hits(&hs), "{:?}", hs);
+        }
+
+        let miss = Rock {
+            start: Vec3i::new(24, 13, 11),
+            direction: Vec3i::new(-3, 1, 2),
+        };
+        let hs = parse::input(include_str!("../example.txt"));
+        assert!(!miss.rock_hits(&hs[0]));
+
+        // collision would be in the past
+        let hs = parse::hailstone("0, 0, 0 @ 1, 1, 1").expect("valid").1;
+        let rock = Rock {
+            start: Vec3i::new(-2, -2, -2),
+            direction: Vec3i::new(-1, -1, -1),
+        };
+        assert!(!rock.rock_hits(&hs));
     }
 
     #[test_log::test]
@@ -10,6 +10,21 @@ fn part1() {
     aoc25::part1(black_box(include_str!("../input.txt")));
 }
 
+#[divan::bench]
+fn part1_stoer_wagner() {
+    aoc25::part1_stoer_wagner(black_box(include_str!("../input.txt")));
+}
+
+#[divan::bench]
+fn part1_search() {
+    aoc25::part1_search(black_box(include_str!("../input.txt")));
+}
+
+#[divan::bench]
+fn part1_betweenness() {
+    aoc25::part1_betweenness(black_box(include_str!("../input.txt")));
+}
+
 #[divan::bench]
 fn part2() {
     aoc25::part2(black_box(include_str!("../input.txt")));
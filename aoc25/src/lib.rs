@@ -1,16 +1,15 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 
-use indicatif::ParallelProgressIterator;
 use bimap::BiMap;
-use indicatif::ProgressBar;
 use itertools::Itertools;
 use petgraph::{
     algo::{connected_components, has_path_connecting, min_spanning_tree},
     data::Element,
-    graph::NodeIndex,
+    graph::{NodeIndex, UnGraph},
+    visit::EdgeRef,
 };
-use rand::{seq::SliceRandom, thread_rng};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use tracing::debug;
 
 mod parse {
     pub fn input(s: &str) -> Vec<(&str, Vec<&str>)> {
@@ -25,18 +24,25 @@ mod parse {
 
 #[derive(Debug, Default)]
 struct Input<'a> {
-    graph: petgraph::graph::UnGraph<&'a str, ()>,
+    graph: UnGraph<&'a str, ()>,
     node_map: BiMap<&'a str, NodeIndex>,
 }
 
 impl<'a> Input<'a> {
     fn from(input: &'a str) -> Self {
+        let edges = parse::input(input)
+            .into_iter()
+            .flat_map(|(a, v)| v.into_iter().map(move |b| (a, b)))
+            .collect::<Vec<_>>();
+
+        Self::from_edges(&edges)
+    }
+
+    fn from_edges(edges: &[(&'a str, &'a str)]) -> Self {
         let mut r = Self::default();
 
-        for (a, v) in parse::input(input) {
-            for b in v {
-                r.add_edge(a, b);
-            }
+        for (a, b) in edges {
+            r.add_edge(a, b);
         }
 
         r
@@ -58,124 +64,418 @@ impl<'a> Input<'a> {
         let b = self.ensure_node(b);
         self.graph.add_edge(a, b, ());
     }
-}
 
-pub fn part1(input: &str) -> usize {
-    let data = Input::from(input);
+    /// Finds the global minimum edge cut of the graph (Stoer-Wagner).
+    ///
+    /// Returns the number of edges in the cut together with the cut edges
+    /// themselves. A graph that is already disconnected has a cut of 0.
+    fn min_cut(&self) -> (usize, Vec<(NodeIndex, NodeIndex)>) {
+        let n = self.graph.node_count();
+        if n < 2 {
+            return (0, Vec::new());
+        }
 
-    eprintln!(
-        "DATA with {} nodes, {} edges",
-        data.graph.node_count(),
-        data.graph.edge_count()
-    );
+        // weights[a][b] is the number of edges between merged nodes a and b
+        let mut weights: Vec<HashMap<usize, usize>> = vec![HashMap::new(); n];
+        for e in self.graph.edge_references() {
+            let (a, b) = (e.source().index(), e.target().index());
+            if a == b {
+                continue;
+            }
+            *weights[a].entry(b).or_default() += 1;
+            *weights[b].entry(a).or_default() += 1;
+        }
 
-    let mut g1 = data.graph.clone();
+        // original nodes that were merged into each node
+        let mut groups: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+        let mut active: Vec<usize> = (0..n).collect();
+        let mut best: Option<(usize, Vec<usize>)> = None;
 
-    /*
-    for ix in g1.node_indices() {
-        eprintln!("NODE {:?} has {} neighbours", data.node_map.get_by_right(&ix), g1.neighbors(ix).count());
-    }
-    println!("{:#?}", Dot::with_config(&g1, &[Config::EdgeNoLabel]));
-    */
+        while active.len() > 1 {
+            // Minimum cut phase: keep adding the most tightly connected node.
+            let mut added = HashSet::new();
+            let mut connectivity: HashMap<usize, usize> = active.iter().map(|i| (*i, 0)).collect();
+            let mut heap: BinaryHeap<(usize, usize)> = active.iter().map(|i| (0, *i)).collect();
 
-    let mut removed_edges = HashSet::new();
+            let mut s = active[0];
+            let mut t = active[0];
+            let mut cut_of_phase = 0;
 
-    while connected_components(&g1) == 1 {
-        eprintln!("Removing ...");
-        let edges = min_spanning_tree(&g1)
-            .filter_map(|e| match e {
-                Element::Edge {
-                    source,
-                    target,
-                    weight: _,
-                } => Some((NodeIndex::new(source), NodeIndex::new(target))),
-                _ => None,
-            })
+            while let Some((w, node)) = heap.pop() {
+                if added.contains(&node) || connectivity[&node] != w {
+                    continue;
+                }
+                added.insert(node);
+                s = t;
+                t = node;
+                cut_of_phase = w;
+
+                for (other, ew) in weights[node].iter() {
+                    if added.contains(other) {
+                        continue;
+                    }
+                    let c = connectivity.get_mut(other).expect("active node");
+                    *c += ew;
+                    heap.push((*c, *other));
+                }
+            }
+
+            if best
+                .as_ref()
+                .map(|(w, _)| cut_of_phase < *w)
+                .unwrap_or(true)
+            {
+                best = Some((cut_of_phase, groups[t].clone()));
+            }
+
+            // merge t into s
+            let t_weights = std::mem::take(&mut weights[t]);
+            for (other, w) in t_weights {
+                weights[other].remove(&t);
+                if other == s {
+                    continue;
+                }
+                *weights[s].entry(other).or_default() += w;
+                *weights[other].entry(s).or_default() += w;
+            }
+            let t_group = std::mem::take(&mut groups[t]);
+            groups[s].extend(t_group);
+            active.retain(|x| *x != t);
+        }
+
+        let (size, side) = best.expect("at least one phase ran");
+        let side = side.into_iter().collect::<HashSet<_>>();
+
+        let edges = self
+            .graph
+            .edge_references()
+            .filter(|e| side.contains(&e.source().index()) != side.contains(&e.target().index()))
+            .map(|e| (e.source(), e.target()))
             .collect::<Vec<_>>();
 
-        for (a, b) in edges {
-            removed_edges.insert((a, b));
-            g1.remove_edge(g1.find_edge(a, b).expect("valid edge"));
+        (size, edges)
+    }
+
+    /// Max flow between `source` and `sink` (every wire has a capacity of 1),
+    /// via Edmonds-Karp. Stops once the flow reaches `limit`.
+    ///
+    /// Returns the flow and the nodes still reachable from `source` in the
+    /// residual graph (i.e. the source side of a minimum cut).
+    fn max_flow(
+        &self,
+        source: NodeIndex,
+        sink: NodeIndex,
+        limit: usize,
+    ) -> (usize, HashSet<NodeIndex>) {
+        // every wire is two arcs: 2*i is source->target, 2*i+1 the reverse.
+        // flow[arc] is in -1..=1, residual capacity is 1 - flow[arc]
+        let ends = self
+            .graph
+            .edge_references()
+            .map(|e| (e.source(), e.target()))
+            .collect::<Vec<_>>();
+        let mut arcs: HashMap<NodeIndex, Vec<usize>> = HashMap::new();
+        for (idx, (a, b)) in ends.iter().enumerate() {
+            arcs.entry(*a).or_default().push(2 * idx);
+            arcs.entry(*b).or_default().push(2 * idx + 1);
+        }
+        let arc_target = |arc: usize| {
+            let (a, b) = ends[arc / 2];
+            if arc.is_multiple_of(2) {
+                b
+            } else {
+                a
+            }
+        };
+        let mut flow = vec![0; 2 * ends.len()];
+
+        let mut total = 0;
+        loop {
+            // BFS for a shortest augmenting path, remembering the arc used
+            // to reach every node
+            let mut reached_by: HashMap<NodeIndex, Option<usize>> = HashMap::new();
+            reached_by.insert(source, None);
+            let mut queue = VecDeque::from([source]);
+
+            while let Some(n) = queue.pop_front() {
+                if n == sink {
+                    break;
+                }
+                for arc in arcs.get(&n).into_iter().flatten() {
+                    let other = arc_target(*arc);
+                    if flow[*arc] < 1 && !reached_by.contains_key(&other) {
+                        reached_by.insert(other, Some(*arc));
+                        queue.push_back(other);
+                    }
+                }
+            }
+
+            if !reached_by.contains_key(&sink) || total >= limit {
+                return (total, reached_by.into_keys().collect());
+            }
+
+            let mut n = sink;
+            while let Some(arc) = reached_by[&n] {
+                flow[arc] += 1;
+                flow[arc ^ 1] -= 1;
+                n = arc_target(arc ^ 1);
+            }
+            total += 1;
         }
     }
 
-    let choices = removed_edges
-        .iter()
-        .filter(|(a, b)| !has_path_connecting(&g1, *a, *b, None))
-        .collect::<Vec<_>>();
+    /// Finds the wires of a global minimum cut, as the smallest max flow
+    /// between a fixed node and every other node.
+    fn global_min_cut_edges(&self) -> Vec<(NodeIndex, NodeIndex)> {
+        let mut nodes = self.graph.node_indices();
+        let Some(source) = nodes.next() else {
+            return Vec::new();
+        };
 
-    let mut tests = choices
-        .iter()
-        .combinations(3)
-        .map(|v| {
-            (
-                ***v.get(0).expect("3 items"),
-                ***v.get(1).expect("3 items"),
-                ***v.get(2).expect("3 items"),
-            )
-        })
-        .collect::<Vec<_>>();
+        let mut best: Option<(usize, HashSet<NodeIndex>)> = None;
+        for sink in nodes {
+            let limit = best.as_ref().map(|(f, _)| *f).unwrap_or(usize::MAX);
+            let (f, side) = self.max_flow(source, sink, limit);
+            if f < limit {
+                best = Some((f, side));
+            }
+        }
 
-    tests.shuffle(&mut thread_rng());
-
-    let s = tests
-        .par_iter()
-        .progress_count(tests.len() as u64)
-        .find_map_any(|(a, b, c)| {
-            let mut g1 = data.graph.clone();
-            g1.remove_edge(g1.find_edge(a.0, a.1).expect("valid edge 1"));
-            g1.remove_edge(g1.find_edge(b.0, b.1).expect("valid edge 2"));
-            g1.remove_edge(g1.find_edge(c.0, c.1).expect("valid edge 3"));
-
-            if connected_components(&g1) == 2 {
-                eprintln!("FOUND:");
-                eprintln!(
-                    "   {:?} - {:?}",
-                    data.node_map.get_by_right(&a.0),
-                    data.node_map.get_by_right(&a.1)
-                );
-                eprintln!(
-                    "   {:?} - {:?}",
-                    data.node_map.get_by_right(&b.0),
-                    data.node_map.get_by_right(&b.1)
-                );
-                eprintln!(
-                    "   {:?} - {:?}",
-                    data.node_map.get_by_right(&b.0),
-                    data.node_map.get_by_right(&c.1)
-                );
-                Some((a, b, c))
-            } else {
-                None
+        let Some((_, side)) = best else {
+            return Vec::new();
+        };
+
+        self.graph
+            .edge_references()
+            .filter(|e| side.contains(&e.source()) != side.contains(&e.target()))
+            .map(|e| (e.source(), e.target()))
+            .collect()
+    }
+
+    /// Estimates how many shortest paths go through every wire, using
+    /// Brandes' algorithm from about 100 evenly spaced start nodes (every
+    /// node on small graphs).
+    ///
+    /// When several shortest paths connect two nodes, each path gets an
+    /// equal share, so scores are fractional rather than whole path counts.
+    /// This keeps the ranking independent of the neighbour iteration order.
+    ///
+    /// Wires connecting two groups carry all paths between them, so they end
+    /// up at the start of the result, sorted by decreasing betweenness.
+    fn edge_betweenness(&self) -> Vec<((NodeIndex, NodeIndex), f64)> {
+        const SAMPLES: usize = 100;
+
+        let n = self.graph.node_count();
+        let step = (n / SAMPLES).max(1);
+        let mut scores: HashMap<(NodeIndex, NodeIndex), f64> = HashMap::new();
+
+        for source in self.graph.node_indices().step_by(step) {
+            // number of shortest paths from source and their length
+            let mut paths = vec![0.0; n];
+            let mut distance = vec![usize::MAX; n];
+            let mut order = Vec::with_capacity(n);
+
+            paths[source.index()] = 1.0;
+            distance[source.index()] = 0;
+            let mut queue = VecDeque::from([source]);
+
+            while let Some(v) = queue.pop_front() {
+                order.push(v);
+                for w in self.graph.neighbors(v) {
+                    if distance[w.index()] == usize::MAX {
+                        distance[w.index()] = distance[v.index()] + 1;
+                        queue.push_back(w);
+                    }
+                    if distance[w.index()] == distance[v.index()] + 1 {
+                        paths[w.index()] += paths[v.index()];
+                    }
+                }
+            }
+
+            // every node spreads its paths back over its predecessors,
+            // farthest nodes first
+            let mut dependency = vec![0.0; n];
+            for w in order.into_iter().rev() {
+                for v in self.graph.neighbors(w) {
+                    if distance[v.index()] + 1 != distance[w.index()] {
+                        continue;
+                    }
+                    let c = paths[v.index()] / paths[w.index()] * (1.0 + dependency[w.index()]);
+                    *scores.entry((v.min(w), v.max(w))).or_default() += c;
+                    dependency[v.index()] += c;
+                }
             }
+        }
+
+        let mut result = scores.into_iter().collect::<Vec<_>>();
+        result.sort_by(|((a1, b1), c1), ((a2, b2), c2)| {
+            c2.total_cmp(c1)
+                .then_with(|| (a1.index(), b1.index()).cmp(&(a2.index(), b2.index())))
         });
+        result
+    }
+
+    /// Sizes of the two groups left after removing the given wires
+    fn group_sizes_without(&self, edges: &[(NodeIndex, NodeIndex)]) -> (usize, usize) {
+        let g1 = self.without_edges(edges);
+
+        let mut s1 = HashSet::new();
+        let mut p = VecDeque::new();
+
+        let start = self.node_map.iter().next().expect("has nodes").1;
+        p.push_back(*start);
+
+        while let Some(n) = p.pop_back() {
+            if s1.contains(&n) {
+                continue;
+            }
+
+            s1.insert(n);
+            for o in g1.neighbors(n) {
+                p.push_back(o);
+            }
+        }
+
+        (s1.len(), self.node_map.len() - s1.len())
+    }
 
-    let mut g1 = data.graph.clone();
-    let (a, b, c) = s.expect("has solution");
-    g1.remove_edge(g1.find_edge(a.0, a.1).expect("valid edge 1"));
-    g1.remove_edge(g1.find_edge(b.0, b.1).expect("valid edge 2"));
-    g1.remove_edge(g1.find_edge(c.0, c.1).expect("valid edge 3"));
+    /// Cuts exactly `k_cut` wires to split the graph into two groups.
+    ///
+    /// Returns the sizes of the two resulting groups.
+    ///
+    /// Panics if the minimum cut of the graph is not `k_cut`.
+    fn split_into(&self, k_cut: usize) -> (usize, usize) {
+        let (cut_size, edges) = self.min_cut();
+        assert_eq!(
+            cut_size, k_cut,
+            "Expected to disconnect the graph by cutting exactly {} wires, however the minimum cut is {}",
+            k_cut, cut_size
+        );
 
-    // at this point g1 has the components ...
-    let mut s1 = HashSet::new();
-    let mut p = VecDeque::new();
+        self.group_sizes_without(&edges)
+    }
+
+    /// Searches for `k_cut` wires splitting the graph into two groups, by
+    /// trying combinations of the wires removed when repeatedly taking out
+    /// spanning trees until the graph disconnects.
+    fn spanning_tree_cut(&self, k_cut: usize) -> Option<Vec<(NodeIndex, NodeIndex)>> {
+        debug!(
+            "DATA with {} nodes, {} edges",
+            self.graph.node_count(),
+            self.graph.edge_count()
+        );
+
+        let mut g1 = self.graph.clone();
+        let mut removed_edges = HashSet::new();
 
-    let start = data.node_map.iter().next().expect("has nodes").1;
-    p.push_back(*start);
+        while connected_components(&g1) == 1 {
+            debug!("Removing spanning tree ...");
+            let edges = min_spanning_tree(&g1)
+                .filter_map(|e| match e {
+                    Element::Edge {
+                        source,
+                        target,
+                        weight: _,
+                    } => Some((NodeIndex::new(source), NodeIndex::new(target))),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
 
-    while let Some(n) = p.pop_back() {
-        if s1.contains(&n) {
-            continue;
+            for (a, b) in edges {
+                removed_edges.insert((a, b));
+                g1.remove_edge(g1.find_edge(a, b).expect("valid edge"));
+            }
         }
 
-        s1.insert(n);
-        for o in g1.neighbors(n) {
-            p.push_back(o);
+        let mut choices = removed_edges
+            .iter()
+            .filter(|(a, b)| !has_path_connecting(&g1, *a, *b, None))
+            .collect::<Vec<_>>();
+
+        // removed_edges is a set, sort to have reproducible runs
+        choices.sort_by_key(|(a, b)| (a.index(), b.index()));
+
+        let tests = choices
+            .iter()
+            .combinations(k_cut)
+            .map(|v| v.into_iter().map(|e| **e).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        tests
+            .par_iter()
+            .find_first(|edges| connected_components(&self.without_edges(edges)) == 2)
+            .cloned()
+    }
+
+    /// Returns a copy of the graph with the given edges removed
+    fn without_edges(&self, edges: &[(NodeIndex, NodeIndex)]) -> UnGraph<&'a str, ()> {
+        let mut g = self.graph.clone();
+        for (a, b) in edges {
+            g.remove_edge(g.find_edge(*a, *b).expect("valid edge"));
         }
+        g
+    }
+}
+
+pub fn part1(input: &str) -> usize {
+    let data = Input::from(input);
+    let edges = data.global_min_cut_edges();
+    assert_eq!(
+        edges.len(),
+        3,
+        "Expected to disconnect the graph by cutting exactly 3 wires, however the minimum cut is {}",
+        edges.len()
+    );
+
+    let (a, b) = data.group_sizes_without(&edges);
+    a * b
+}
+
+/// Same as [part1], however finds the wires to cut via a Stoer-Wagner
+/// minimum cut.
+pub fn part1_stoer_wagner(input: &str) -> usize {
+    let (a, b) = Input::from(input).split_into(3);
+    a * b
+}
+
+/// Same as [part1], however searches for the wires to cut through
+/// combinations of spanning tree edges.
+pub fn part1_search(input: &str) -> usize {
+    let data = Input::from(input);
+    let edges = data.spanning_tree_cut(3).expect("has solution");
+    for (a, b) in edges.iter() {
+        debug!(
+            "Cutting {:?} - {:?}",
+            data.node_map.get_by_right(a),
+            data.node_map.get_by_right(b)
+        );
     }
 
-    eprintln!("{} out of {}", s1.len(), data.node_map.len());
-    s1.len() * (data.node_map.len() - s1.len())
+    let (a, b) = data.group_sizes_without(&edges);
+    a * b
+}
+
+/// Same as [part1], however tries to cut combinations of the wires with
+/// the highest edge betweenness.
+pub fn part1_betweenness(input: &str) -> usize {
+    const CANDIDATES: usize = 10;
+
+    let data = Input::from(input);
+    let candidates = data
+        .edge_betweenness()
+        .into_iter()
+        .take(CANDIDATES)
+        .map(|(e, _)| e)
+        .collect::<Vec<_>>();
+
+    let edges = candidates
+        .into_iter()
+        .combinations(3)
+        .find(|edges| connected_components(&data.without_edges(edges)) == 2)
+        .expect("a 3-wire cut among the top candidates");
+
+    let (a, b) = data.group_sizes_without(&edges);
+    a * b
 }
 
 pub fn part2(_input: &str) -> usize {
@@ -192,6 +492,120 @@ mod tests {
         assert_eq!(part1(include_str!("../example.txt")), 54);
     }
 
+    #[test]
+    fn test_part1_stoer_wagner() {
+        assert_eq!(part1_stoer_wagner(include_str!("../example.txt")), 54);
+    }
+
+    #[test]
+    fn test_part1_search() {
+        assert_eq!(part1_search(include_str!("../example.txt")), 54);
+    }
+
+    #[test]
+    fn test_global_min_cut_edges() {
+        let data = Input::from(include_str!("../example.txt"));
+        let mut edges = data
+            .global_min_cut_edges()
+            .into_iter()
+            .map(|(a, b)| {
+                let mut names = [
+                    *data.node_map.get_by_right(&a).expect("valid"),
+                    *data.node_map.get_by_right(&b).expect("valid"),
+                ];
+                names.sort();
+                names
+            })
+            .collect::<Vec<_>>();
+        edges.sort();
+        assert_eq!(edges, [["bvb", "cmg"], ["hfx", "pzl"], ["jqt", "nvd"]]);
+
+        let (a, b) = data.group_sizes_without(&data.global_min_cut_edges());
+        assert_eq!(a * b, 54);
+
+        // two triangles connected by a single wire
+        let data = Input::from("a: b c\nb: c\nc: d\nd: e f\ne: f");
+        assert_eq!(data.global_min_cut_edges().len(), 1);
+        assert_eq!(
+            data.group_sizes_without(&data.global_min_cut_edges()),
+            (3, 3)
+        );
+    }
+
+    #[test]
+    fn test_part1_betweenness() {
+        assert_eq!(part1_betweenness(include_str!("../example.txt")), 54);
+    }
+
+    #[test]
+    fn test_edge_betweenness() {
+        let data = Input::from(include_str!("../example.txt"));
+        let betweenness = data.edge_betweenness();
+        assert_eq!(betweenness.len(), data.graph.edge_count());
+
+        let mut top = betweenness
+            .iter()
+            .take(3)
+            .map(|((a, b), _)| {
+                let mut names = [
+                    *data.node_map.get_by_right(a).expect("valid"),
+                    *data.node_map.get_by_right(b).expect("valid"),
+                ];
+                names.sort();
+                names
+            })
+            .collect::<Vec<_>>();
+        top.sort();
+        assert_eq!(top, [["bvb", "cmg"], ["hfx", "pzl"], ["jqt", "nvd"]]);
+
+        // in a square every wire is on the same number of shortest paths
+        let data = Input::from("a: b d\nc: b d");
+        let betweenness = data.edge_betweenness();
+        assert_eq!(betweenness.len(), 4);
+        assert!(betweenness.iter().all(|(_, c)| *c == betweenness[0].1));
+    }
+
+    #[test]
+    fn test_part1_repeatable() {
+        for _ in 0..10 {
+            assert_eq!(part1(include_str!("../example.txt")), 54);
+            assert_eq!(part1_search(include_str!("../example.txt")), 54);
+        }
+    }
+
+    #[test]
+    fn test_min_cut() {
+        let data = Input::from(include_str!("../example.txt"));
+        let (size, edges) = data.min_cut();
+        assert_eq!(size, 3);
+        assert_eq!(edges.len(), 3);
+
+        // two triangles connected by two edges
+        let data = Input::from("a: b c\nb: c\nc: d\nd: e f\ne: f\nf: a");
+        let (size, edges) = data.min_cut();
+        assert_eq!(size, 2);
+        assert_eq!(edges.len(), 2);
+
+        // already disconnected
+        let data = Input::from("a: b\nc: d");
+        assert_eq!(data.min_cut(), (0, Vec::new()));
+    }
+
+    #[test]
+    fn test_split_from_edges() {
+        // two triangles connected by a single wire
+        let data = Input::from_edges(&[
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("d", "e"),
+            ("e", "f"),
+            ("f", "d"),
+            ("c", "d"),
+        ]);
+        assert_eq!(data.split_into(1), (3, 3));
+    }
+
     #[test]
     fn test_part2() {
         assert_eq!(part2(include_str!("../example.txt")), 0);
@@ -1,7 +1,4 @@
-use aoc3::{
-    alternate_part_1_sum_parts, alternate_part_2_sum_gear_ratios, part_1_sum_parts,
-    part_2_sum_gear_ratios,
-};
+use aoc3::{alternate_part_1_sum_parts, alternate_part_2_sum_gear_ratios};
 
 fn main() {
     // Run registered benchmarks.
@@ -10,12 +7,12 @@ fn main() {
 
 #[divan::bench]
 fn part1() {
-    part_1_sum_parts(divan::black_box(include_str!("../input.txt")));
+    aoc3::part1(divan::black_box(include_str!("../input.txt")));
 }
 
 #[divan::bench]
 fn part2() {
-    part_2_sum_gear_ratios(divan::black_box(include_str!("../input.txt")));
+    aoc3::part2(divan::black_box(include_str!("../input.txt")));
 }
 
 #[divan::bench]
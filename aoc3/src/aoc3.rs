@@ -1,26 +1,35 @@
-use aoc3::{
-    alternate_part_1_sum_parts, alternate_part_2_sum_gear_ratios, part_1_sum_parts,
-    part_2_sum_gear_ratios,
-};
+use aoc3::{alternate_part_1_sum_parts, alternate_part_2_sum_gear_ratios, part1, part2};
 
 #[cfg(feature = "dhat-heap")]
 #[global_allocator]
 static ALLOC: dhat::Alloc = dhat::Alloc;
 
+/// Reads the input at `path` (`-` for stdin), defaulting to the embedded
+/// puzzle input.
+fn load_input(path: Option<&str>) -> String {
+    runner::read_input(path, include_str!("../input.txt"))
+        .unwrap_or_else(|e| panic!("Failed to read input {:?}: {}", path, e))
+}
+
 #[tracing::instrument]
 fn main() {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
 
-    let s1 = part_1_sum_parts(include_str!("../input.txt"));
+    // Both implementations can be compared on other inputs using:
+    //
+    //   cargo run -p aoc3 -- path/to/input.txt
+    let input = load_input(std::env::args().nth(1).as_deref());
+
+    let s1 = part1(&input);
     println!("Part 1: {}", s1);
 
-    let s2 = part_2_sum_gear_ratios(include_str!("../input.txt"));
+    let s2 = part2(&input);
     println!("Part 2: {}", s2);
 
-    let s1a = alternate_part_1_sum_parts(include_str!("../input.txt"));
+    let s1a = alternate_part_1_sum_parts(&input);
     println!("Part 1 (Alternate): {}", s1a);
 
-    let s2a = alternate_part_2_sum_gear_ratios(include_str!("../input.txt"));
+    let s2a = alternate_part_2_sum_gear_ratios(&input);
     println!("Part 2 (Alternate): {}", s2a);
 }
@@ -16,6 +16,14 @@ pub struct PartItem {
 
 impl PartItem {
     pub fn is_adjacent_part_number(&self, symbol: &PartItem) -> bool {
+        self.is_adjacent_with(symbol, true)
+    }
+
+    /// Checks if the symbol is adjacent to this part number.
+    ///
+    /// When `diagonal` is false, symbols touching only a corner of the
+    /// part number are not considered adjacent.
+    pub fn is_adjacent_with(&self, symbol: &PartItem, diagonal: bool) -> bool {
         // we only consider symbols
         assert!(matches!(symbol.item_type, ItemType::Symbol(_)));
         assert!(symbol.len == 1);
@@ -29,6 +37,11 @@ impl PartItem {
             return false;
         }
 
+        if !diagonal && symbol.line != self.line {
+            // directly above or below the number
+            return (symbol.col >= self.col) && (symbol.col < self.col + self.len);
+        }
+
         // should be within range
         (symbol.col + 1 >= self.col) && (symbol.col <= self.col + self.len)
     }
@@ -163,7 +176,7 @@ pub fn parts(input: &str) -> Vec<Part> {
 
         match s.len() {
             0 => {}
-            1 => match (n, s.get(0)) {
+            1 => match (n, s.first()) {
                 (
                     PartItem {
                         item_type: ItemType::PartNumber(number),
@@ -192,12 +205,15 @@ pub struct Gear {
 }
 
 impl Gear {
-    pub fn ratio(&self) -> u32 {
-        self.n1 * self.n2
+    pub fn ratio(&self) -> u64 {
+        // two u32 values always fit in a u64 product
+        u64::from(self.n1) * u64::from(self.n2)
     }
 }
 
-pub fn gears(input: &str) -> Vec<Gear> {
+/// Finds the part numbers adjacent to every `symbol` that has
+/// exactly `count` adjacent part numbers.
+pub fn symbol_groups(input: &str, symbol: char, count: usize) -> Vec<Vec<u32>> {
     let (symbols, numbers): (Vec<_>, Vec<_>) = PartItemIterator::new(input)
         .partition(|part| matches!(part.item_type, ItemType::Symbol(_)));
 
@@ -205,7 +221,7 @@ pub fn gears(input: &str) -> Vec<Gear> {
 
     for s in symbols
         .iter()
-        .filter(|s| s.item_type == ItemType::Symbol('*'))
+        .filter(|s| s.item_type == ItemType::Symbol(symbol))
     {
         // Find all numbers that are associated to this symbol
         let n = numbers
@@ -221,22 +237,51 @@ pub fn gears(input: &str) -> Vec<Gear> {
             })
             .collect::<Vec<_>>();
 
-        if n.len() == 2 {
-            result.push(Gear {
-                n1: *n.first().unwrap(),
-                n2: *n.last().unwrap(),
-            })
+        if n.len() == count {
+            result.push(n)
         }
     }
     result
 }
 
-pub fn part_1_sum_parts(input: &str) -> u32 {
-    parts(input).iter().map(|p| p.number).sum()
+pub fn gears(input: &str) -> Vec<Gear> {
+    symbol_groups(input, '*', 2)
+        .into_iter()
+        .map(|n| Gear {
+            n1: *n.first().unwrap(),
+            n2: *n.last().unwrap(),
+        })
+        .collect()
+}
+
+/// Sums all part numbers, returning an error if the sum overflows.
+pub fn part_1_sum_parts(input: &str) -> Result<u64, String> {
+    parts(input)
+        .iter()
+        .try_fold(0u64, |sum, p| sum.checked_add(u64::from(p.number)))
+        .ok_or_else(|| "Part number sum overflows".to_string())
+}
+
+/// Sums all gear ratios, returning an error if the sum overflows.
+pub fn part_2_sum_gear_ratios(input: &str) -> Result<u64, String> {
+    gears(input)
+        .iter()
+        .try_fold(0u64, |sum, g| {
+            u64::from(g.n1)
+                .checked_mul(u64::from(g.n2))
+                .and_then(|ratio| sum.checked_add(ratio))
+        })
+        .ok_or_else(|| "Gear ratio sum overflows".to_string())
+}
+
+/// Same as [part_1_sum_parts], but panics on overflow.
+pub fn part1(input: &str) -> u64 {
+    part_1_sum_parts(input).expect("part number sum fits in u64")
 }
 
-pub fn part_2_sum_gear_ratios(input: &str) -> u32 {
-    gears(input).iter().map(|g| g.ratio()).sum()
+/// Same as [part_2_sum_gear_ratios], but panics on overflow.
+pub fn part2(input: &str) -> u64 {
+    part_2_sum_gear_ratios(input).expect("gear ratio sum fits in u64")
 }
 
 //////// Totaly alternate implementation
@@ -352,9 +397,20 @@ impl Board {
 
         result
     }
+
+    /// All the numbers touching the given symbol, each number reported once
+    /// and ordered by position (line, then column).
+    pub fn adjacent_numbers(&self, pos: SymbolPos) -> Vec<u32> {
+        let mut labels = self
+            .labels_around(pos.line, pos.col)
+            .into_iter()
+            .collect::<Vec<_>>();
+        labels.sort();
+        labels.into_iter().map(|l| l.label).collect()
+    }
 }
 
-pub fn alternate_part_1_sum_parts(input: &str) -> u32 {
+pub fn alternate_part_1_sum_parts(input: &str) -> u64 {
     let mut data = HashSet::new();
     let board = Board::new(input);
 
@@ -365,28 +421,30 @@ pub fn alternate_part_1_sum_parts(input: &str) -> u32 {
             data.insert(label);
         }
     }
-    data.iter().map(|l| l.label).sum()
+    data.iter().map(|l| u64::from(l.label)).sum()
 }
 
-pub fn alternate_part_2_sum_gear_ratios(input: &str) -> u32 {
+/// Returns every `*` symbol together with all the numbers touching it.
+///
+/// Numbers are ordered by their position (line, then column). No filtering
+/// is done on the number count, so entries may have 0, 1, 2 or more numbers.
+pub fn gear_candidates(input: &str) -> Vec<(SymbolPos, Vec<u32>)> {
     let board = Board::new(input);
 
-    let mut power_sum = 0;
-    for symbol in board.symbols() {
-        if symbol.symbol != '*' {
-            continue;
-        }
-        let labels = board.labels_around(symbol.line, symbol.col);
-        if labels.len() != 2 {
-            continue;
-        }
-        let mut power = 1;
-        for x in labels {
-            power *= x.label;
-        }
-        power_sum += power;
-    }
-    power_sum
+    board
+        .symbols()
+        .into_iter()
+        .filter(|symbol| symbol.symbol == '*')
+        .map(|symbol| (symbol, board.adjacent_numbers(symbol)))
+        .collect()
+}
+
+pub fn alternate_part_2_sum_gear_ratios(input: &str) -> u64 {
+    gear_candidates(input)
+        .into_iter()
+        .filter(|(_, labels)| labels.len() == 2)
+        .map(|(_, labels)| labels.iter().map(|l| u64::from(*l)).product::<u64>())
+        .sum()
 }
 
 #[cfg(test)]
@@ -416,6 +474,31 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_adjacent_numbers() {
+        let board = Board::new(include_str!("../example.txt"));
+
+        assert_eq!(
+            board.adjacent_numbers(SymbolPos {
+                symbol: '*',
+                line: 1,
+                col: 3
+            }),
+            vec![467, 35]
+        );
+
+        // multi-digit numbers touching in several places are reported once
+        let board = Board::new("123\n.#.\n4.5");
+        assert_eq!(
+            board.adjacent_numbers(SymbolPos {
+                symbol: '#',
+                line: 1,
+                col: 1
+            }),
+            vec![123, 4, 5]
+        );
+    }
+
     #[test]
     fn test_labels() {
         let board = Board::new(include_str!("../example.txt"));
@@ -505,11 +588,93 @@ mod tests {
             gears(include_str!("../example.txt"))
                 .iter()
                 .map(|g| g.ratio())
-                .sum::<u32>(),
+                .sum::<u64>(),
             467835
         );
     }
 
+    #[test]
+    fn test_checked_sums() {
+        assert_eq!(part1(include_str!("../example.txt")), 4361);
+        assert_eq!(part2(include_str!("../example.txt")), 467835);
+
+        // ratio overflows u32, but fits in u64
+        let input = "100000*100000";
+        assert_eq!(part_2_sum_gear_ratios(input), Ok(10_000_000_000));
+        assert_eq!(part_1_sum_parts(input), Ok(200_000));
+
+        // two gears of (u32::MAX)^2 overflow u64
+        let input = "4294967295*4294967295\n.....................\n4294967295*4294967295";
+        assert!(part_2_sum_gear_ratios(input).is_err());
+        assert_eq!(part_1_sum_parts(input), Ok(4 * u64::from(u32::MAX)));
+    }
+
+    #[test]
+    fn test_symbol_groups() {
+        let input = "
+1.2.3
+.+..+
+4..56
++....
+7...8
+        "
+        .trim();
+        assert_eq!(symbol_groups(input, '+', 3), [vec![1, 2, 4]]);
+        assert_eq!(symbol_groups(input, '+', 2), [vec![3, 56], vec![4, 7]]);
+        assert!(symbol_groups(input, '*', 2).is_empty());
+    }
+
+    #[test]
+    fn test_gear_candidates() {
+        let input = "
+1.2.3
+.*..*
+4..56
+*....
+7...8
+        "
+        .trim();
+
+        assert_eq!(
+            gear_candidates(input),
+            [
+                (
+                    SymbolPos {
+                        symbol: '*',
+                        line: 1,
+                        col: 1
+                    },
+                    vec![1, 2, 4]
+                ),
+                (
+                    SymbolPos {
+                        symbol: '*',
+                        line: 1,
+                        col: 4
+                    },
+                    vec![3, 56]
+                ),
+                (
+                    SymbolPos {
+                        symbol: '*',
+                        line: 3,
+                        col: 0
+                    },
+                    vec![4, 7]
+                ),
+            ]
+        );
+        assert_eq!(alternate_part_2_sum_gear_ratios(input), 3 * 56 + 4 * 7);
+
+        assert_eq!(
+            gear_candidates(include_str!("../example.txt"))
+                .into_iter()
+                .map(|(_, n)| n)
+                .collect::<Vec<_>>(),
+            [vec![467, 35], vec![617], vec![755, 598]]
+        );
+    }
+
     #[test]
     fn test_adjacency() {
         let (symbols, numbers): (Vec<_>, Vec<_>) =
@@ -539,7 +704,7 @@ mod tests {
             4361
         );
 
-        assert_eq!(part_1_sum_parts(include_str!("../example.txt")), 4361);
+        assert_eq!(part_1_sum_parts(include_str!("../example.txt")), Ok(4361));
     }
 
     #[test]
@@ -735,6 +900,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn adjacent_diagonal_test() {
+        let n = PartItem {
+            item_type: ItemType::PartNumber(123),
+            len: 3,
+            line: 10,
+            col: 10,
+        };
+
+        let sym = |line: u32, col: u32| PartItem {
+            item_type: ItemType::Symbol('x'),
+            len: 1,
+            line,
+            col,
+        };
+
+        // corners
+        for (line, col) in [(9, 9), (9, 13), (11, 9), (11, 13)] {
+            assert!(n.is_adjacent_with(&sym(line, col), true));
+            assert!(!n.is_adjacent_with(&sym(line, col), false));
+        }
+
+        // orthogonal
+        for (line, col) in [(9, 10), (9, 12), (10, 9), (10, 13), (11, 11)] {
+            assert!(n.is_adjacent_with(&sym(line, col), true));
+            assert!(n.is_adjacent_with(&sym(line, col), false));
+        }
+    }
+
     #[test]
     fn parse_parts() {
         assert!(PartItemIterator::new("").eq([]));
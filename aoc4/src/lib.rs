@@ -1,4 +1,7 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashSet, VecDeque},
+    io::BufRead,
+};
 
 use nom::{
     bytes::complete::tag,
@@ -50,8 +53,19 @@ impl Card {
         lines.split('\n').map(Card::parse).collect()
     }
 
+    /// Numbers that are both winning and actual, sorted
+    pub fn matched_numbers(&self) -> Vec<u32> {
+        let mut matched = self
+            .winning
+            .intersection(&self.actual)
+            .copied()
+            .collect::<Vec<_>>();
+        matched.sort();
+        matched
+    }
+
     pub fn wins(&self) -> usize {
-        self.winning.intersection(&self.actual).count()
+        self.matched_numbers().len()
     }
 
     pub fn points(&self) -> usize {
@@ -70,6 +84,15 @@ pub fn part_1_add_points(lines: &str) -> usize {
         .sum()
 }
 
+/// Per card `(id, wins, points)`, in input order
+pub fn card_reports(lines: &str) -> Vec<(u32, usize, usize)> {
+    Card::parse_many(lines)
+        .expect("valid input")
+        .iter()
+        .map(|c| (c.num, c.wins(), c.points()))
+        .collect()
+}
+
 pub fn part_2_sum_cards(lines: &str) -> usize {
     let cards = Card::parse_many(lines).expect("valid input");
     let mut counts: Vec<usize> = Vec::with_capacity(cards.len());
@@ -92,8 +115,46 @@ pub fn part_2_sum_cards(lines: &str) -> usize {
     counts.iter().sum()
 }
 
+/// Reads cards one line at a time from the given reader
+fn read_cards<R: BufRead>(r: R) -> impl Iterator<Item = Result<Card, String>> {
+    r.lines().map(|line| match line {
+        Ok(line) => Card::parse(&line),
+        Err(e) => Err(format!("Error reading: {}", e)),
+    })
+}
+
+/// Same as [part_1_add_points], however streams cards from a reader
+pub fn part_1_reader<R: BufRead>(r: R) -> Result<usize, String> {
+    read_cards(r).map(|card| card.map(|c| c.points())).sum()
+}
+
+/// Same as [part_2_sum_cards], however streams cards from a reader
+pub fn part_2_reader<R: BufRead>(r: R) -> Result<usize, String> {
+    // extra copies won for the upcoming cards
+    let mut pending: VecDeque<usize> = VecDeque::new();
+    let mut total = 0;
+
+    for card in read_cards(r) {
+        let count = 1 + pending.pop_front().unwrap_or(0);
+        let wins = card?.wins();
+
+        if pending.len() < wins {
+            pending.resize(wins, 0);
+        }
+        for cnt in pending.iter_mut().take(wins) {
+            *cnt += count;
+        }
+
+        total += count;
+    }
+
+    Ok(total)
+}
+
 #[cfg(test)]
 mod tests {
+    use std::io::Cursor;
+
     use rstest::rstest;
 
     use crate::*;
@@ -109,11 +170,48 @@ mod tests {
         assert_eq!(part_1_add_points(include_str!("../example.txt")), 13);
     }
 
+    #[test]
+    fn test_card_reports() {
+        assert_eq!(
+            card_reports(include_str!("../example.txt")),
+            vec![
+                (1, 4, 8),
+                (2, 2, 2),
+                (3, 2, 2),
+                (4, 1, 1),
+                (5, 0, 0),
+                (6, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matched_numbers() {
+        let cards = Card::parse_many(include_str!("../example.txt")).expect("Valid example");
+
+        assert_eq!(cards[0].matched_numbers(), vec![17, 48, 83, 86]);
+        assert_eq!(cards[3].matched_numbers(), vec![84]);
+        assert_eq!(cards[5].matched_numbers(), vec![]);
+    }
+
     #[test]
     fn test_part2() {
         assert_eq!(part_2_sum_cards(include_str!("../example.txt")), 30);
     }
 
+    #[test]
+    fn test_reader() {
+        assert_eq!(
+            part_1_reader(Cursor::new(include_str!("../example.txt"))),
+            Ok(13)
+        );
+        assert_eq!(
+            part_2_reader(Cursor::new(include_str!("../example.txt"))),
+            Ok(30)
+        );
+        assert!(part_1_reader(Cursor::new("Card 1: 1 2 | 3\nfoo")).is_err());
+    }
+
     #[test]
     fn test_parse_many() {
         let cards = Card::parse_many(include_str!("../example.txt")).expect("Valid example");
@@ -128,7 +226,7 @@ mod tests {
             })
         );
 
-        assert_eq!(cards.get(0).expect("Valid").points(), 8);
+        assert_eq!(cards.first().expect("Valid").points(), 8);
         assert_eq!(cards.get(1).expect("Valid").points(), 2);
         assert_eq!(cards.get(2).expect("Valid").points(), 2);
         assert_eq!(cards.get(3).expect("Valid").points(), 1);
@@ -1,5 +1,3 @@
-use aoc5::{part_1_min, part_2_min};
-
 #[cfg(feature = "dhat-heap")]
 #[global_allocator]
 static ALLOC: dhat::Alloc = dhat::Alloc;
@@ -9,9 +7,9 @@ fn main() {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
 
-    let s1 = part_1_min(include_str!("../input.txt"));
-    println!("Part 1: {}", s1);
+    let input = runner::load_input(include_str!("../input.txt"));
+    let (s1, s2) = aoc5::solve(&input);
 
-    let s2 = part_2_min(include_str!("../input.txt"));
+    println!("Part 1: {}", s1);
     println!("Part 2: {}", s2);
 }
@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    io::{BufRead, Lines},
+};
 
 use nom::{
     bytes::complete::tag,
@@ -124,6 +127,11 @@ impl MapKey<'_> {
     }
 }
 
+fn parse_seeds(span: &str) -> IResult<&str, Vec<i64>> {
+    let (span, _) = tuple((tag("seeds:"), space1)).parse(span)?;
+    separated_list1(space1, nom::character::complete::i64).parse(span)
+}
+
 #[derive(Clone, Default, Debug, PartialEq)]
 pub struct InputData<'a> {
     seeds: Vec<i64>,
@@ -131,18 +139,24 @@ pub struct InputData<'a> {
 }
 
 impl InputData<'_> {
+    /// Seeds interpreted as part 2 ranges: pairs of start and length,
+    /// returned as `(start, end)` with `end` NOT included.
+    pub fn seed_ranges(&self) -> Vec<(i64, i64)> {
+        self.seeds.chunks(2).map(|w| (w[0], w[0] + w[1])).collect()
+    }
+
     pub fn get_map_from(&self, state: &str) -> Option<&MapKey<'_>> {
-        for k in self.maps.keys() {
-            if k.from == state {
-                return Some(k);
-            }
-        }
-        None
+        self.maps.keys().find(|k| k.from == state)
     }
 
-    pub fn place(&self, mut value: i64, name: &str) -> i64 {
-        let mut state = "seed";
-        while state != name {
+    pub fn place(&self, value: i64, name: &str) -> i64 {
+        self.place_from(value, "seed", name)
+    }
+
+    /// Maps a value in the `from` category into the `to` category
+    pub fn place_from(&self, mut value: i64, from: &str, to: &str) -> i64 {
+        let mut state = from;
+        while state != to {
             let key = self.get_map_from(state).expect("valid input");
             for m in self.maps.get(key).expect("valid input") {
                 if let Some(new_pos) = m.try_map(value) {
@@ -157,10 +171,9 @@ impl InputData<'_> {
         value
     }
 
-    pub fn parse(span: &str) -> IResult<&str, InputData> {
+    pub fn parse(span: &str) -> IResult<&str, InputData<'_>> {
         // start with seeds map
-        let (span, _) = tuple((tag("seeds:"), space1)).parse(span)?;
-        let (span, seeds) = separated_list1(space1, nom::character::complete::i64).parse(span)?;
+        let (span, seeds) = parse_seeds(span)?;
         let (span, _) = tag("\n").parse(span)?;
 
         let (span, mappings) = many0(
@@ -179,8 +192,128 @@ impl InputData<'_> {
     }
 }
 
+/// Reads input line by line: the seeds first, followed by one map at a time.
+///
+/// Maps are expected to be in order, i.e. each map starts where the
+/// previous one ended (seed -> soil -> ... -> location).
+struct InputReader<R> {
+    lines: Lines<R>,
+    state: String,
+}
+
+impl<R: BufRead> InputReader<R> {
+    fn new(r: R) -> Self {
+        Self {
+            lines: r.lines(),
+            state: "seed".into(),
+        }
+    }
+
+    fn next_line(&mut self) -> Result<Option<String>, String> {
+        self.lines
+            .next()
+            .transpose()
+            .map_err(|e| format!("Error reading: {}", e))
+    }
+
+    fn seeds(&mut self) -> Result<Vec<i64>, String> {
+        let line = self.next_line()?.ok_or("Missing seeds")?;
+
+        parse_seeds(&line)
+            .map(|(_, seeds)| seeds)
+            .map_err(|e| format!("Error parsing: {:?}", e))
+    }
+
+    /// Reads the next map, returning None once all input was consumed
+    fn next_map(&mut self) -> Result<Option<Vec<MapRange>>, String> {
+        let header = loop {
+            match self.next_line()? {
+                None => return Ok(None),
+                Some(l) if l.is_empty() => continue,
+                Some(l) => break l,
+            }
+        };
+
+        let key = MapKey::parse(&header)
+            .map_err(|e| format!("Error parsing: {:?}", e))?
+            .1;
+        if key.from != self.state {
+            return Err(format!(
+                "Expected a map from {}, got {}-to-{}",
+                self.state, key.from, key.to
+            ));
+        }
+        self.state = key.to.into();
+
+        let mut ranges = Vec::new();
+        while let Some(line) = self.next_line()? {
+            if line.is_empty() {
+                break;
+            }
+            ranges.push(
+                MapRange::parse(&line)
+                    .map_err(|e| format!("Error parsing: {:?}", e))?
+                    .1,
+            );
+        }
+
+        Ok(Some(ranges))
+    }
+
+    /// Validates that all maps were read and that they ended in the given state
+    fn finish(&self, name: &str) -> Result<(), String> {
+        if self.state != name {
+            return Err(format!("Maps end at {}, expected {}", self.state, name));
+        }
+        Ok(())
+    }
+}
+
+/// Same as [part_1_min], however streams the input from a reader
+pub fn part_1_reader<R: BufRead>(r: R) -> Result<i64, String> {
+    let mut input = InputReader::new(r);
+    let mut values = input.seeds()?;
+
+    while let Some(ranges) = input.next_map()? {
+        values = values
+            .into_iter()
+            .map(|v| ranges.try_map(v).unwrap_or(v))
+            .collect();
+    }
+    input.finish("location")?;
+
+    values.into_iter().min().ok_or("No seeds".into())
+}
+
+/// Same as [part_2_min], however streams the input from a reader
+pub fn part_2_reader<R: BufRead>(r: R) -> Result<i64, String> {
+    let mut input = InputReader::new(r);
+    let mut maps = input
+        .seeds()?
+        .chunks(2)
+        .map(|w| match w {
+            [start, len] => Ok(MapRange::from_to_len(*start, *start, *len)),
+            _ => Err("Seeds must be start/length pairs".to_string()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    while let Some(ranges) = input.next_map()? {
+        maps = maps.iter().flat_map(|m| m.transform(&ranges)).collect();
+        maps.sort();
+    }
+    input.finish("location")?;
+
+    maps.iter()
+        .map(|m| m.try_map(m.source_start).unwrap_or(m.source_start))
+        .min()
+        .ok_or("No seeds".into())
+}
+
 pub fn part_1_min(input: &str) -> i64 {
-    let data = InputData::parse(input).expect("good input").1;
+    min_location(&InputData::parse(input).expect("good input").1)
+}
+
+fn min_location(data: &InputData) -> i64 {
     data.seeds
         .iter()
         .map(|s| data.place(*s, "location"))
@@ -188,15 +321,14 @@ pub fn part_1_min(input: &str) -> i64 {
         .unwrap()
 }
 
-pub fn part_2_min(input: &str) -> i64 {
-    let data = InputData::parse(input).expect("good input").1;
-
+/// Maps the seed ranges all the way to location ranges
+fn location_ranges(data: &InputData) -> Vec<MapRange> {
     // every data seed is an identity map ....
     // //
     let mut maps = data
-        .seeds
-        .chunks(2)
-        .map(|w| MapRange::from_to_len(w[0], w[0], w[1]))
+        .seed_ranges()
+        .into_iter()
+        .map(|(start, end)| MapRange::from_start_end(start, end, start))
         .collect::<Vec<_>>();
 
     let mut state = "seed";
@@ -205,21 +337,74 @@ pub fn part_2_min(input: &str) -> i64 {
         let key = data.get_map_from(state).expect("valid input");
         maps = maps
             .iter()
-            .flat_map(|m| m.transform(&data.maps.get(key).expect("valid input")))
+            .flat_map(|m| m.transform(data.maps.get(key).expect("valid input")))
             .collect();
         maps.sort();
         state = key.to;
     }
 
-    // minimum will be at one of the starts
-    maps.iter()
-        .map(|m| m.try_map(m.source_start).unwrap_or(m.source_start))
+    maps
+}
+
+pub fn part_2_min(input: &str) -> i64 {
+    part_2_min_seed(input).0
+}
+
+/// Same as [part_2_min], returning `(min_location, seed)` where `seed` is
+/// the seed that ends up in `min_location`.
+pub fn part_2_min_seed(input: &str) -> (i64, i64) {
+    min_range_location(&InputData::parse(input).expect("good input").1)
+}
+
+fn min_range_location(data: &InputData) -> (i64, i64) {
+    // minimum will be at one of the starts. Ranges map seeds to locations,
+    // so the start is the seed.
+    location_ranges(data)
+        .iter()
+        .map(|m| {
+            (
+                m.try_map(m.source_start).unwrap_or(m.source_start),
+                m.source_start,
+            )
+        })
         .min()
         .unwrap()
 }
 
+/// Computes both the [part_1_min] and [part_2_min] answers, parsing the
+/// input only once.
+pub fn solve(input: &str) -> (i64, i64) {
+    let data = InputData::parse(input).expect("good input").1;
+    (min_location(&data), min_range_location(&data).0)
+}
+
+/// Number of distinct locations reachable from the seed ranges
+pub fn part_2_reachable_count(input: &str) -> i64 {
+    let data = InputData::parse(input).expect("good input").1;
+
+    // location ranges as start/end (NOT inclusive)
+    let mut locations = location_ranges(&data)
+        .iter()
+        .map(|m| (m.dest_start, m.dest_start + m.source_end - m.source_start))
+        .collect::<Vec<_>>();
+    locations.sort();
+
+    // coalesce overlapping ranges
+    let mut merged: Vec<(i64, i64)> = Vec::new();
+    for (start, end) in locations {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    merged.iter().map(|(start, end)| end - start).sum()
+}
+
 #[cfg(test)]
 mod tests {
+    use std::io::Cursor;
+
     use crate::*;
 
     #[test]
@@ -232,6 +417,61 @@ mod tests {
         assert_eq!(part_2_min(include_str!("../example.txt")), 46);
     }
 
+    #[test]
+    fn test_seed_ranges() {
+        let data = InputData::parse(include_str!("../example.txt"))
+            .expect("valid")
+            .1;
+        assert_eq!(data.seeds, vec![79, 14, 55, 13]);
+        assert_eq!(data.seed_ranges(), vec![(79, 93), (55, 68)]);
+    }
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(solve(include_str!("../example.txt")), (35, 46));
+    }
+
+    #[test]
+    fn test_part2_min_seed() {
+        let example = include_str!("../example.txt");
+        assert_eq!(part_2_min_seed(example), (46, 82));
+
+        let data = InputData::parse(example).expect("valid").1;
+        assert_eq!(data.place(82, "location"), 46);
+    }
+
+    #[test]
+    fn test_reachable_count() {
+        assert_eq!(part_2_reachable_count(include_str!("../example.txt")), 27);
+        assert_eq!(
+            part_2_reachable_count("seeds: 1 10 5 10\n\nseed-to-location map:\n100 1 3\n"),
+            14
+        );
+    }
+
+    #[test]
+    fn test_reader() {
+        assert_eq!(
+            part_1_reader(Cursor::new(include_str!("../example.txt"))),
+            Ok(35)
+        );
+        assert_eq!(
+            part_2_reader(Cursor::new(include_str!("../example.txt"))),
+            Ok(46)
+        );
+        assert_eq!(
+            part_1_reader(Cursor::new(include_str!("../input.txt"))),
+            Ok(part_1_min(include_str!("../input.txt")))
+        );
+        assert_eq!(
+            part_2_reader(Cursor::new(include_str!("../input.txt"))),
+            Ok(part_2_min(include_str!("../input.txt")))
+        );
+
+        assert!(part_1_reader(Cursor::new("seeds: 1 2\n\nsoil-to-seed map:\n1 2 3")).is_err());
+        assert!(part_1_reader(Cursor::new("seeds: 1 2\n\nseed-to-soil map:\n1 2 3")).is_err());
+    }
+
     #[test]
     fn test_chunk_map() {
         assert_eq!(
@@ -290,6 +530,17 @@ mod tests {
         assert_eq!(r.place(13, "location"), 35);
     }
 
+    #[test]
+    fn test_place_from() {
+        let r = InputData::parse(include_str!("../example.txt"))
+            .expect("valid input")
+            .1;
+        assert_eq!(r.place_from(81, "soil", "fertilizer"), 81);
+        assert_eq!(r.place_from(14, "soil", "fertilizer"), 53);
+        assert_eq!(r.place_from(81, "soil", "humidity"), 78);
+        assert_eq!(r.place_from(78, "humidity", "humidity"), 78);
+    }
+
     #[test]
     fn test_parse_input() {
         let r = InputData::parse(include_str!("../example.txt"))
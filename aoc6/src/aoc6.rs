@@ -9,9 +9,5 @@ fn main() {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
 
-    let s1 = part_1(include_str!("../input.txt"));
-    println!("Part 1: {}", s1);
-
-    let s2 = part_2(include_str!("../input.txt"));
-    println!("Part 2: {}", s2);
+    runner::run(include_str!("../input.txt"), part_1, part_2);
 }
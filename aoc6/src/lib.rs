@@ -1,6 +1,6 @@
 use nom::{
     bytes::complete::tag,
-    character::complete::{multispace0, multispace1, space1},
+    character::complete::{multispace0, multispace1, space0, space1},
     combinator::recognize,
     multi::separated_list1,
     sequence::{delimited, tuple},
@@ -15,23 +15,70 @@ pub struct Race {
 
 impl Race {
     pub fn trave_distance(&self, press: u64) -> u64 {
-        return (self.time - press) * press;
+        (self.time - press) * press
     }
 
+    /// Number of presses that beat the record, computed on integers only.
+    pub fn win_counts_exact(&self) -> u128 {
+        let t = self.time as u128;
+        let record = self.record as u128;
+        let distance = |press: u128| (t - press) * press;
+
+        // winning presses are strictly between the roots of
+        // press * (t - press) = record
+        let disc = match (t * t).checked_sub(4 * record) {
+            Some(disc) => disc,
+            None => return 0,
+        };
+
+        // isqrt rounds down, so this is at most the first win. Distances are
+        // symmetric, so the last win is t - p1
+        let mut p1 = (t - disc.isqrt()) / 2;
+        while p1 <= t / 2 && distance(p1) <= record {
+            p1 += 1;
+        }
+
+        if p1 > t / 2 {
+            return 0;
+        }
+        t - 2 * p1 + 1
+    }
+
+    /// Same as [Race::win_counts_exact], using floating point math. Only
+    /// kept for comparison.
     pub fn win_counts(&self) -> usize {
         let t = self.time as f64;
-        let disc = t * t - ((4 * self.record) as f64 + 0.000000000001);
+        let disc = t * t - (4 * self.record) as f64;
 
         if disc < 0.0 {
             eprintln!("{:?}: {}", self, disc);
             return 0;
         }
         let disc = disc.sqrt();
-        let mut p1 = (t - disc) / 2.0;
-        let mut p2 = (t + disc) / 2.0;
-        p1 = p1.ceil();
-        p2 = p2.floor();
-        (p2 - p1 + 1.0) as usize
+        let mut p1 = ((t - disc) / 2.0).ceil().max(0.0) as u64;
+        let mut p2 = (((t + disc) / 2.0).floor() as u64).min(self.time);
+
+        // Edges may only tie the record (or be off due to float precision),
+        // which is not a win
+        while p1 <= p2 && self.trave_distance(p1) <= self.record {
+            p1 += 1;
+        }
+        while p2 > p1 && self.trave_distance(p2) <= self.record {
+            p2 -= 1;
+        }
+
+        if p1 > p2 {
+            return 0;
+        }
+        (p2 - p1 + 1) as usize
+    }
+
+    /// Press time that travels the furthest and the distance travelled.
+    ///
+    /// For odd times both halves are equally good, the lower one is returned.
+    pub fn optimal_press(&self) -> (u64, u64) {
+        let press = self.time / 2;
+        (press, self.trave_distance(press))
     }
 }
 
@@ -40,68 +87,79 @@ pub struct InputData {
     pub races: Vec<Race>,
 }
 
-pub fn parse_input_kernig(input: &str) -> IResult<&str, InputData> {
+/// The Time and Distance numbers, as written in the input
+fn number_lists(input: &str) -> IResult<&str, (Vec<&str>, Vec<&str>)> {
+    let numbers = || separated_list1(space1, recognize(nom::character::complete::u64));
+
     tuple((
-        delimited(
-            tuple((tag("Time:"), space1)),
-            separated_list1(space1, recognize(nom::character::complete::u64)),
-            multispace1,
-        )
-        .map(|items| items.join("").parse()),
-        delimited(
-            tuple((tag::<&str, _, _>("Distance:"), space1)),
-            separated_list1(space1, recognize(nom::character::complete::u64)),
-            multispace0,
-        )
-        .map(|items| items.join("").parse()),
+        delimited(tuple((tag("Time:"), space0)), numbers(), multispace1),
+        delimited(tuple((tag("Distance:"), space0)), numbers(), multispace0),
     ))
-    .map(|(time, distance)| InputData {
-        races: time
-            .iter()
-            .zip(distance)
-            .map(|(time, record)| Race {
-                time: *time,
-                record,
-            })
-            .collect(),
-    })
     .parse(input)
 }
 
-pub fn parse_input(input: &str) -> IResult<&str, InputData> {
-    tuple((
-        delimited(
-            tuple((tag("Time:"), space1)),
-            separated_list1(space1, nom::character::complete::u64),
-            multispace1,
-        ),
-        delimited(
-            tuple((tag::<&str, _, _>("Distance:"), space1)),
-            separated_list1(space1, nom::character::complete::u64),
-            multispace0,
-        ),
-    ))
-    .map(|(time, distance)| InputData {
-        races: time
-            .iter()
-            .zip(distance)
-            .map(|(time, record)| Race {
-                time: *time,
-                record,
-            })
-            .collect(),
+/// Parses the Time and Distance lists, validating that they have the same
+/// length and that nothing else follows them.
+fn parse_lists(input: &str) -> Result<(Vec<&str>, Vec<&str>), String> {
+    let (rest, (times, distances)) =
+        number_lists(input).map_err(|e| format!("Error parsing: {:?}", e))?;
+
+    if !rest.is_empty() {
+        return Err(format!("Unparsed input: {:?}", rest));
+    }
+    if times.len() != distances.len() {
+        return Err(format!(
+            "Found {} times but {} distances",
+            times.len(),
+            distances.len()
+        ));
+    }
+
+    Ok((times, distances))
+}
+
+fn parse_number(s: &str) -> Result<u64, String> {
+    s.parse()
+        .map_err(|e| format!("Invalid number {:?}: {}", s, e))
+}
+
+/// Parses the input as a single race, ignoring the spaces between digits
+pub fn parse_input_kernig(input: &str) -> Result<InputData, String> {
+    let (times, distances) = parse_lists(input)?;
+
+    Ok(InputData {
+        races: vec![Race {
+            time: parse_number(&times.join(""))?,
+            record: parse_number(&distances.join(""))?,
+        }],
     })
-    .parse(input)
 }
 
-pub fn part_1(input: &str) -> usize {
-    let data = parse_input(input).expect("valid input").1;
-    data.races.iter().map(|r| r.win_counts()).product()
+pub fn parse_input(input: &str) -> Result<InputData, String> {
+    let (times, distances) = parse_lists(input)?;
+
+    let races = times
+        .iter()
+        .zip(distances)
+        .map(|(time, record)| {
+            Ok(Race {
+                time: parse_number(time)?,
+                record: parse_number(record)?,
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(InputData { races })
+}
+
+pub fn part_1(input: &str) -> u128 {
+    let data = parse_input(input).expect("valid input");
+    data.races.iter().map(|r| r.win_counts_exact()).product()
 }
 
-pub fn part_2(input: &str) -> usize {
-    let data = parse_input_kernig(input).expect("valid input").1;
-    data.races.iter().map(|r| r.win_counts()).product()
+pub fn part_2(input: &str) -> u128 {
+    let data = parse_input_kernig(input).expect("valid input");
+    data.races.iter().map(|r| r.win_counts_exact()).product()
 }
 
 #[cfg(test)]
@@ -113,17 +171,109 @@ mod tests {
         assert_eq!(part_1(include_str!("../example.txt")), 288);
     }
 
+    #[test]
+    fn test_optimal_press() {
+        let race = Race { time: 7, record: 9 };
+        let (press, distance) = race.optimal_press();
+        assert!(press == 3 || press == 4);
+        assert_eq!(distance, 12);
+        assert_eq!(race.trave_distance(7 - press), distance);
+
+        assert_eq!(
+            Race {
+                time: 30,
+                record: 200
+            }
+            .optimal_press(),
+            (15, 225)
+        );
+    }
+
+    #[test]
+    fn test_win_counts_ties() {
+        let wins = |time, record| Race { time, record }.win_counts();
+
+        // best press exactly ties the record
+        assert_eq!(wins(6, 9), 0);
+        assert_eq!(wins(7, 12), 0);
+
+        // 3 and 5 tie, only 4 wins
+        assert_eq!(wins(8, 15), 1);
+
+        // 10 and 20 tie, 11 to 19 win
+        assert_eq!(wins(30, 200), 9);
+    }
+
+    #[test]
+    fn test_win_counts_exact() {
+        let example = include_str!("../example.txt");
+
+        for data in [parse_input(example), parse_input_kernig(example)] {
+            for race in data.expect("valid input").races {
+                assert_eq!(race.win_counts_exact(), race.win_counts() as u128);
+            }
+        }
+
+        let wins = |time, record| Race { time, record }.win_counts_exact();
+        assert_eq!(wins(7, 9), 4);
+        assert_eq!(wins(71530, 940200), 71503);
+        assert_eq!(wins(6, 9), 0);
+        assert_eq!(wins(8, 15), 1);
+        assert_eq!(wins(30, 200), 9);
+        assert_eq!(wins(5, 100), 0);
+        assert_eq!(wins(0, 0), 0);
+
+        // kerned sized races, where floats lose precision
+        assert_eq!(wins(1 << 40, 0), (1 << 40) - 1);
+        assert_eq!(wins(u64::MAX, 0), u64::MAX as u128 - 1);
+        let t = 4_000_000_000_u64;
+        assert_eq!(wins(t, (t / 2) * (t / 2) - 1), 1);
+    }
+
     #[test]
     fn test_part2() {
         assert_eq!(part_2(include_str!("../example.txt")), 71503);
     }
 
+    #[test]
+    fn test_parse_whitespace() {
+        let example = parse_input(include_str!("../example.txt")).expect("valid input");
+
+        assert_eq!(
+            parse_input("Time:\t7\t15 \t30\nDistance:  9\t\t40\t200\n"),
+            Ok(example.clone())
+        );
+        assert_eq!(
+            parse_input("Time:7 15 30  \n\nDistance:9 40 200"),
+            Ok(example)
+        );
+        assert_eq!(
+            parse_input_kernig("Time:\t7\t15\t30\nDistance:\t9\t40\t200").map(|d| d.races),
+            Ok(vec![Race {
+                time: 71530,
+                record: 940200
+            }])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_input("Time: 7 15 30\nDistance: 9 40"),
+            Err("Found 3 times but 2 distances".into())
+        );
+        assert_eq!(
+            parse_input_kernig("Time: 7 15\nDistance: 9 40 200"),
+            Err("Found 2 times but 3 distances".into())
+        );
+        assert!(parse_input("Time: 7\nDistance: 9\nTime: 1").is_err());
+        assert!(parse_input("Distance: 9\nTime: 7").is_err());
+    }
+
     #[test]
     fn test_parse_input_kernig() {
         assert_eq!(
-            parse_input_kernig(include_str!("../example.txt"))
-                .expect("valid input")
-                .1,
+            parse_input_kernig(include_str!("../example.txt")).expect("valid input"),
             InputData {
                 races: vec![Race {
                     time: 71530,
@@ -136,9 +286,7 @@ mod tests {
     #[test]
     fn test_parse_input() {
         assert_eq!(
-            parse_input(include_str!("../example.txt"))
-                .expect("valid input")
-                .1,
+            parse_input(include_str!("../example.txt")).expect("valid input"),
             InputData {
                 races: vec![
                     Race { time: 7, record: 9 },
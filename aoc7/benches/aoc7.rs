@@ -1,4 +1,4 @@
-use aoc7::{part2_score, part1_score};
+use aoc7::{part1_score, part2_score, score};
 use divan::black_box;
 
 fn main() {
@@ -15,3 +15,13 @@ fn part1() {
 fn part2() {
     part2_score(black_box(include_str!("../input.txt")));
 }
+
+#[divan::bench]
+fn score_no_joker() {
+    score(black_box(include_str!("../input.txt")), false).expect("valid input");
+}
+
+#[divan::bench]
+fn score_joker() {
+    score(black_box(include_str!("../input.txt")), true).expect("valid input");
+}
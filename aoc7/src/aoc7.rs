@@ -1,5 +1,3 @@
-use aoc7::{part1_score, part2_score};
-
 #[cfg(feature = "dhat-heap")]
 #[global_allocator]
 static ALLOC: dhat::Alloc = dhat::Alloc;
@@ -9,9 +7,9 @@ fn main() {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
 
-    let s1 = part1_score(include_str!("../input.txt"));
-    println!("Part 1: {}", s1);
+    let input = runner::load_input(include_str!("../input.txt"));
+    let (s1, s2) = aoc7::solve(&input).expect("valid input");
 
-    let s2 = part2_score(include_str!("../input.txt"));
+    println!("Part 1: {}", s1);
     println!("Part 2: {}", s2);
 }
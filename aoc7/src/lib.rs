@@ -163,7 +163,7 @@ impl Hand {
     }
 
     pub fn hand_type(&self) -> Type {
-        match self.items.get(0).expect("valid hand") {
+        match self.items.first().expect("valid hand") {
             Item::Five(_) => Type::FiveOfAKind,
             Item::Four(_) => Type::FourOfAKind,
             Item::Three(_) => match self.items.get(1).expect("valid input") {
@@ -285,9 +285,21 @@ pub fn parse_input(input: &str) -> IResult<&str, Vec<Bid>> {
     multi::many1(parse_bid.terminated(opt(multispace0))).parse(input)
 }
 
-pub fn part1_score(input: &str) -> usize {
-    let (left, mut bids) = parse_input(input).expect("valid input");
-    assert_eq!(left, "");
+fn parse_bids(input: &str) -> Result<Vec<Bid>, String> {
+    let (left, bids) = parse_input(input).map_err(|e| format!("Error parsing: {:?}", e))?;
+    if !left.is_empty() {
+        return Err(format!("Unparsed input: {:?}", left));
+    }
+    Ok(bids)
+}
+
+/// Ranks all the bids and computes the total winnings.
+fn total_winnings(mut bids: Vec<Bid>, joker: bool) -> usize {
+    if joker {
+        for b in bids.iter_mut() {
+            b.hand = b.hand.as_joker_hand();
+        }
+    }
 
     // smallest hand goes first
     bids.sort();
@@ -297,24 +309,29 @@ pub fn part1_score(input: &str) -> usize {
         .sum()
 }
 
-pub fn part2_score(input: &str) -> usize {
-    let (left, bids) = parse_input(input).expect("valid input");
-    assert_eq!(left, "");
+/// Ranks all the hands in the input and computes the total winnings.
+///
+/// When `joker` is set, `J` cards are jokers (see [Hand::as_joker_hand]).
+pub fn score(input: &str, joker: bool) -> Result<usize, String> {
+    Ok(total_winnings(parse_bids(input)?, joker))
+}
 
-    // smallest hand goes first
-    let mut bids = bids
-        .iter()
-        .map(|b| Bid {
-            hand: b.hand.as_joker_hand(),
-            value: b.value,
-        })
-        .collect::<Vec<_>>();
+/// Computes both the [part1_score] and [part2_score] answers,
+/// parsing the input only once.
+pub fn solve(input: &str) -> Result<(usize, usize), String> {
+    let bids = parse_bids(input)?;
+    Ok((
+        total_winnings(bids.clone(), false),
+        total_winnings(bids, true),
+    ))
+}
 
-    bids.sort();
-    bids.iter()
-        .enumerate()
-        .map(|(rank, bid)| (rank + 1) * bid.value as usize)
-        .sum()
+pub fn part1_score(input: &str) -> usize {
+    score(input, false).expect("valid input")
+}
+
+pub fn part2_score(input: &str) -> usize {
+    score(input, true).expect("valid input")
 }
 
 // Stategy:
@@ -334,6 +351,22 @@ mod tests {
         assert_eq!(part2_score(include_str!("../example.txt")), 5905);
     }
 
+    #[test]
+    fn test_score() {
+        assert_eq!(score(include_str!("../example.txt"), false), Ok(6440));
+        assert_eq!(score(include_str!("../example.txt"), true), Ok(5905));
+
+        assert!(score("32T3K 765\nT5X5J 684", false).is_err());
+        assert!(score("32T3K 765\nT5X5J 684", true).is_err());
+        assert!(score("Z", false).is_err());
+    }
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(solve(include_str!("../example.txt")), Ok((6440, 5905)));
+        assert!(solve("32T3K 765\nT5X5J 684").is_err());
+    }
+
     #[test]
     fn check_input_parse() {
         assert_eq!(
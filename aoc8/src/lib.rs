@@ -50,7 +50,7 @@ impl<'a> Location<'a> {
     }
 }
 
-fn parse_location(input: &str) -> IResult<&str, Location> {
+fn parse_location(input: &str) -> IResult<&str, Location<'_>> {
     recognize(many_m_n(3, 3, none_of("=(), \n")))
         .map(Location::new)
         .parse(input)
@@ -63,7 +63,7 @@ struct LocationMap<'a> {
     right: Location<'a>,
 }
 
-fn parse_location_map(input: &str) -> IResult<&str, LocationMap> {
+fn parse_location_map(input: &str) -> IResult<&str, LocationMap<'_>> {
     tuple((
         parse_location,
         parse_location.preceded_by(tag(" = (")),
@@ -79,7 +79,7 @@ struct InputData<'a> {
     map_list: Vec<LocationMap<'a>>,
 }
 
-fn parse_input(input: &str) -> IResult<&str, InputData> {
+fn parse_input(input: &str) -> IResult<&str, InputData<'_>> {
     let (span, result) = tuple((
         parse_direction_list.terminated(multispace1),
         many1(parse_location_map.terminated(multispace0)),
@@ -100,7 +100,7 @@ struct DirectionLoop {
 }
 
 impl DirectionLoop {
-    pub fn iter(&self) -> DirectionIter {
+    pub fn iter(&self) -> DirectionIter<'_> {
         DirectionIter {
             steps: &self.steps,
             pos: 0,
@@ -199,6 +199,16 @@ impl<'a> Ghost<'a> {
         }
     }
 
+    /// If the ghost returns to its first stop every `time` steps (and never
+    /// goes through another stop), returns that period.
+    fn simple_period(&self) -> Option<usize> {
+        let start = FillKey(self.step, self.position);
+        match self.next_stop.get(&start) {
+            Some((steps, next)) if *next == start && *steps == self.time => Some(self.time),
+            _ => None,
+        }
+    }
+
     fn move_to_next_stop(&mut self) {
         // we are at time, position
         let p = FillKey(self.step, self.position);
@@ -225,23 +235,72 @@ impl<'a> From<InputData<'a>> for Map<'a> {
     }
 }
 
-pub fn part1_steps(input: &str) -> usize {
+/// Steps needed to walk from `start_name` to the first location
+/// matching `is_end` (the start itself is not checked).
+///
+/// Returns None if no such location is ever reached.
+fn steps_until<F: Fn(&Location) -> bool>(
+    input: &str,
+    start_name: &str,
+    is_end: F,
+) -> Option<usize> {
     let map: Map = parse_input(input).expect("valid input").1.into();
-    let target = Location::new("ZZZ");
-    let mut position = &Location::new("AAA");
+    let mut position = &Location::new(start_name);
+
+    // after this many steps some (location, direction index) repeated,
+    // so the walk is in a loop
+    let max_steps = map.directions.steps.len() * map.map.len();
 
-    for (i, d) in map.directions.iter().enumerate() {
+    for (i, d) in map.directions.iter().enumerate().take(max_steps) {
+        let (left, right) = map.map.get(position)?;
         position = match d {
-            Direction::Left => &map.map.get(position).expect("valid").0,
-            Direction::Right => &map.map.get(position).expect("valid").1,
+            Direction::Left => left,
+            Direction::Right => right,
         };
 
-        if *position == target {
-            return i + 1;
+        if is_end(position) {
+            return Some(i + 1);
         }
     }
 
-    panic!("should never finish")
+    None
+}
+
+/// Walks from `start` until reaching a ghost end (`..Z`) twice, returning
+/// `(first_z_step, subsequent_period)`.
+///
+/// The LCM shortcut for part 2 is only valid if both are equal for every
+/// ghost start.
+///
+/// Panics if `start` does not reach a ghost end twice.
+pub fn cycle_info(input: &str, start: &str) -> (usize, usize) {
+    let map: Map = parse_input(input).expect("valid input").1.into();
+    let mut position = &Location::new(start);
+    let mut first_z = None;
+
+    // same bound as in steps_until, for each of the two ends
+    let max_steps = 2 * map.directions.steps.len() * map.map.len();
+
+    for (i, d) in map.directions.iter().enumerate().take(max_steps) {
+        let (left, right) = map.map.get(position).expect("valid location");
+        position = match d {
+            Direction::Left => left,
+            Direction::Right => right,
+        };
+
+        if position.is_ghost_end() {
+            match first_z {
+                None => first_z = Some(i + 1),
+                Some(first) => return (first, i + 1 - first),
+            }
+        }
+    }
+
+    panic!("{} does not reach a ghost end twice", start)
+}
+
+pub fn part1_steps(input: &str) -> usize {
+    steps_until(input, "AAA", |l| l.name == "ZZZ").expect("ZZZ is reachable")
 }
 
 pub fn part2_steps(input: &str) -> usize {
@@ -258,6 +317,18 @@ pub fn part2_steps(input: &str) -> usize {
         .map(|p| Ghost::new(p, &map))
         .collect::<Vec<_>>();
 
+    // Ghosts that loop back to their only stop all meet at the LCM
+    // of their periods
+    if let Some(periods) = ghosts
+        .iter()
+        .map(|g| g.simple_period())
+        .collect::<Option<Vec<_>>>()
+    {
+        return periods
+            .into_iter()
+            .fold(1, |acc, p| mathutil::lcm(acc as u64, p as u64) as usize);
+    }
+
     loop {
         let a = ghosts.iter().map(|g| g.time).min().expect("have ghosts");
         let b = ghosts.iter().map(|g| g.time).max().expect("have ghosts");
@@ -288,6 +359,62 @@ mod tests {
         assert_eq!(part2_steps(include_str!("../example2.txt")), 6);
     }
 
+    #[test]
+    fn test_part2_cycles() {
+        // every ghost loops to its only stop: solved via LCM
+        let input = "L
+
+11A = (11B, XXX)
+11B = (11Z, XXX)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, XXX)
+22C = (22Z, XXX)
+22Z = (22B, XXX)
+XXX = (XXX, XXX)";
+        assert_eq!(part2_steps(input), 6);
+
+        // 11A stops at t=1 and then every 2 steps, so the ghosts need
+        // to be moved in lockstep rather than using their LCM
+        let input = "LR
+
+11A = (11Z, XXX)
+11B = (11Z, 11Z)
+11Z = (11B, 11B)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+        assert_eq!(part2_steps(input), 3);
+    }
+
+    #[test]
+    fn test_steps_until() {
+        let example = include_str!("../example2.txt");
+
+        assert_eq!(steps_until(example, "11A", |l| l.is_ghost_end()), Some(2));
+        assert_eq!(
+            steps_until(example, "22A", |l| l.name.ends_with('Z')),
+            Some(3)
+        );
+        assert_eq!(steps_until(example, "11A", |l| l.name == "22Z"), None);
+        assert_eq!(steps_until(example, "ZZZ", |_| true), None);
+    }
+
+    #[test]
+    fn test_cycle_info() {
+        let example = include_str!("../example2.txt");
+
+        assert_eq!(cycle_info(example, "11A"), (2, 2));
+        assert_eq!(cycle_info(example, "22A"), (3, 3));
+
+        for start in ["11A", "22A"] {
+            let (offset, period) = cycle_info(example, start);
+            assert_eq!(offset, period, "{}", start);
+        }
+    }
+
     #[test]
     fn test_direction_loop_iterate() {
         let d = DirectionLoop {
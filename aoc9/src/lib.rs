@@ -18,23 +18,32 @@ fn parse_sequence(input: &str) -> IResult<&str, Sequence> {
 }
 
 impl Sequence {
-    #[tracing::instrument(name="towers of sequence")]
-    pub fn towers(&self) -> Vec<Vec<i64>> {
-        let mut towers = Vec::new();
+    /// All difference rows, starting with the values themselves and ending
+    /// with the all-zero row. Uses i128 so that differences and
+    /// extrapolations of large i64 values cannot overflow.
+    pub fn difference_pyramid(&self) -> Vec<Vec<i128>> {
+        let mut values = self.values.iter().map(|v| *v as i128).collect::<Vec<_>>();
+        let mut pyramid = Vec::new();
 
-        let mut values = self.values.clone();
         while !values.iter().all(|v| *v == 0) {
-            towers.push(values.clone());
-            values = values.iter()
-                .tuple_windows()
-                .map(|(a, b)| b - a)
-                .collect();
+            let next = values.iter().tuple_windows().map(|(a, b)| b - a).collect();
+            pyramid.push(values);
+            values = next;
         }
+        pyramid.push(values);
+        pyramid
+    }
+
+    /// Computes the difference rows, without the final all-zero row.
+    #[tracing::instrument(name="towers of sequence")]
+    pub fn towers(&self) -> Vec<Vec<i128>> {
+        let mut towers = self.difference_pyramid();
+        towers.pop();
         info!("TOWERS: {:?}", &towers);
         towers
     }
 
-    pub fn next_tower_sum(&self) -> i64 {
+    pub fn next_tower_sum(&self) -> i128 {
         // Computes the next value in a tower...
         self.towers()
             .iter()
@@ -42,7 +51,7 @@ impl Sequence {
             .fold(0, |acc, x| acc + x.last().expect("non-empty"))
     }
 
-    pub fn previous_tower_sum(&self) -> i64 {
+    pub fn previous_tower_sum(&self) -> i128 {
         // Computes the next value in a tower...
         self.towers()
             .iter()
@@ -62,14 +71,14 @@ fn parse_input(input: &str) -> IResult<&str, Input> {
         .parse(input)
 }
 
-pub fn part1(input: &str) -> i64 {
+pub fn part1(input: &str) -> i128 {
     let (rest, input) = parse_input(input).expect("Valid input");
     assert_eq!(rest, "");
 
     input.sequences.iter().map(|s| s.next_tower_sum()).sum()
 }
 
-pub fn part2(input: &str) -> i64 {
+pub fn part2(input: &str) -> i128 {
     let (rest, input) = parse_input(input).expect("Valid input");
     assert_eq!(rest, "");
 
@@ -93,6 +102,37 @@ mod tests {
         assert_eq!(part2("0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45"), 2);
     }
 
+    #[test_log::test]
+    fn test_no_overflow() {
+        const X: i64 = i64::MAX / 2;
+        let s = Sequence {
+            values: vec![0, X, 2 * X],
+        };
+
+        assert_eq!(s.next_tower_sum(), 3 * (X as i128));
+        assert!(s.next_tower_sum() > i64::MAX as i128);
+
+        let s = Sequence {
+            values: vec![2 * X, X, 0],
+        };
+        assert_eq!(s.previous_tower_sum(), 3 * (X as i128));
+    }
+
+    #[test_log::test]
+    fn test_difference_pyramid() {
+        let s = parse_sequence("0 3 6 9 12 15").expect("valid").1;
+
+        assert_eq!(
+            s.difference_pyramid(),
+            vec![
+                vec![0, 3, 6, 9, 12, 15],
+                vec![3, 3, 3, 3, 3],
+                vec![0, 0, 0, 0]
+            ]
+        );
+        assert_eq!(s.towers(), s.difference_pyramid()[..2]);
+    }
+
     #[test_log::test]
     fn test_parse_input() {
         assert_eq!(
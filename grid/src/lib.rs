@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+/// A position within a grid, as (row, col)
+pub type Position = (usize, usize);
+
+/// Parses a rectangular grid of characters.
+///
+/// Returns every character keyed by its (row, col) position, followed by
+/// the number of rows and columns.
+///
+/// Panics if rows do not all have the same length.
+pub fn parse_char_grid(input: &str) -> (HashMap<(i32, i32), char>, usize, usize) {
+    let mut data = HashMap::with_capacity(input.len());
+    let mut rows = 0;
+    let mut cols = None;
+
+    for (row, l) in input.lines().enumerate() {
+        let len = l.chars().count();
+        match cols {
+            Some(v) => assert_eq!(len, v, "row {} has a different length", row),
+            None => cols = Some(len),
+        }
+
+        for (col, c) in l.chars().enumerate() {
+            data.insert((row as i32, col as i32), c);
+        }
+        rows += 1;
+    }
+
+    (data, rows, cols.unwrap_or(0))
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn all() -> [Direction; 4] {
+        [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]
+    }
+
+    /// Returns the (row, col) delta of a single step in this direction
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+        }
+    }
+
+    pub fn inverse(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+/// A rectangular grid of values, stored row by row
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from row-major data.
+    ///
+    /// Panics if data does not contain exactly rows*cols items.
+    pub fn new(rows: usize, cols: usize, data: Vec<T>) -> Self {
+        assert_eq!(rows * cols, data.len(), "Grid data size mismatch");
+        Self { rows, cols, data }
+    }
+
+    pub fn filled(rows: usize, cols: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(rows, cols, vec![value; rows * cols])
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns (rows, cols)
+    pub fn dim(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn contains(&self, (row, col): Position) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    pub fn get(&self, pos: Position) -> Option<&T> {
+        if self.contains(pos) {
+            self.data.get(pos.0 * self.cols + pos.1)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: Position) -> Option<&mut T> {
+        if self.contains(pos) {
+            self.data.get_mut(pos.0 * self.cols + pos.1)
+        } else {
+            None
+        }
+    }
+
+    /// Moves `amount` steps from `pos` in the given direction, returning
+    /// None if that would end up outside the grid.
+    pub fn step_by(&self, pos: Position, d: Direction, amount: usize) -> Option<Position> {
+        let (dr, dc) = d.delta();
+        let row = pos.0.checked_add_signed(dr as isize * amount as isize)?;
+        let col = pos.1.checked_add_signed(dc as isize * amount as isize)?;
+        self.contains((row, col)).then_some((row, col))
+    }
+
+    /// Moves a single step from `pos` in the given direction, returning
+    /// None if that would end up outside the grid.
+    pub fn step(&self, pos: Position, d: Direction) -> Option<Position> {
+        self.step_by(pos, d, 1)
+    }
+
+    /// Iterates over all valid neighbours of the given position, together
+    /// with the direction used to reach them.
+    pub fn neighbours(&self, pos: Position) -> impl Iterator<Item = (Direction, Position)> + '_ {
+        Direction::all()
+            .into_iter()
+            .filter_map(move |d| self.step(pos, d).map(|p| (d, p)))
+    }
+
+    /// Iterates over all positions and values, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.data
+            .iter()
+            .enumerate()
+            .map(|(idx, v)| ((idx / self.cols, idx % self.cols), v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction() {
+        for d in Direction::all() {
+            assert_eq!(d.inverse().inverse(), d);
+            let (r, c) = d.delta();
+            let (ir, ic) = d.inverse().delta();
+            assert_eq!((r + ir, c + ic), (0, 0));
+        }
+    }
+
+    #[test]
+    fn test_parse_char_grid() {
+        let (data, rows, cols) = parse_char_grid("#.S\n..#\n");
+
+        assert_eq!((rows, cols), (2, 3));
+        assert_eq!(data.len(), 6);
+        assert_eq!(data.get(&(0, 2)), Some(&'S'));
+        assert_eq!(data.get(&(1, 2)), Some(&'#'));
+        assert_eq!(data.get(&(2, 0)), None);
+
+        assert_eq!(parse_char_grid(""), (HashMap::new(), 0, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_parse_char_grid_uneven() {
+        parse_char_grid("#.S\n..");
+    }
+
+    #[test]
+    fn test_grid_access() {
+        let mut g = Grid::new(2, 3, vec![1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(g.dim(), (2, 3));
+        assert_eq!(g.get((0, 0)), Some(&1));
+        assert_eq!(g.get((1, 2)), Some(&6));
+        assert_eq!(g.get((2, 0)), None);
+        assert_eq!(g.get((0, 3)), None);
+
+        *g.get_mut((1, 1)).expect("valid") = 10;
+        assert_eq!(g.get((1, 1)), Some(&10));
+    }
+
+    #[test]
+    fn test_grid_steps() {
+        let g = Grid::filled(3, 3, ());
+
+        assert_eq!(g.step((0, 0), Direction::Up), None);
+        assert_eq!(g.step((0, 0), Direction::Right), Some((0, 1)));
+        assert_eq!(g.step_by((0, 0), Direction::Down, 2), Some((2, 0)));
+        assert_eq!(g.step_by((0, 0), Direction::Down, 3), None);
+
+        assert_eq!(
+            g.neighbours((0, 0)).collect::<Vec<_>>(),
+            vec![(Direction::Down, (1, 0)), (Direction::Right, (0, 1))]
+        );
+        assert_eq!(g.neighbours((1, 1)).count(), 4);
+    }
+}
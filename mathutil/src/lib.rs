@@ -0,0 +1,79 @@
+/// Greatest common divisor. `gcd(0, 0)` is 0.
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or `None` if it does not fit in a `u64`.
+///
+/// `checked_lcm(x, 0)` is 0.
+pub fn checked_lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    let value = (a as u128 / gcd(a, b) as u128) * b as u128;
+    u64::try_from(value).ok()
+}
+
+/// Least common multiple.
+///
+/// Panics if the result does not fit in a `u64`.
+pub fn lcm(a: u64, b: u64) -> u64 {
+    checked_lcm(a, b).expect("lcm fits in u64")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(18, 12), 6);
+        assert_eq!(gcd(7, 13), 1);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd(5, 0), 5);
+        assert_eq!(gcd(0, 0), 0);
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(2, 3), 6);
+        assert_eq!(lcm(5, 0), 0);
+        assert_eq!(lcm(1 << 40, 1 << 50), 1 << 50);
+        assert_eq!(lcm(u64::MAX, u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(checked_lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(checked_lcm(1 << 32, (1 << 32) + 1), None);
+        assert_eq!(
+            checked_lcm(1 << 31, (1 << 32) + 1),
+            Some((1 << 31) * ((1 << 32) + 1))
+        );
+    }
+
+    #[test]
+    fn test_lcm_gcd_product() {
+        for a in 1..200u64 {
+            for b in 1..200u64 {
+                assert_eq!(lcm(a, b) * gcd(a, b), a * b, "a={a}, b={b}");
+                assert_eq!(a % gcd(a, b), 0);
+                assert_eq!(lcm(a, b) % b, 0);
+            }
+        }
+
+        // large values: products are checked in u128 to not overflow
+        for (a, b) in [(u64::MAX, 3), (1 << 63, 6), (4294967291, 4294967279)] {
+            let product = a as u128 * b as u128;
+            match checked_lcm(a, b) {
+                Some(l) => assert_eq!(l as u128 * gcd(a, b) as u128, product),
+                None => assert!(product / gcd(a, b) as u128 > u64::MAX as u128),
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt::Display;
+
+/// Error for an input that could not be fully parsed
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError {
+    /// Line where parsing stopped (1-based)
+    pub line: usize,
+    /// Column where parsing stopped (1-based, in characters)
+    pub column: usize,
+    /// The input left unparsed
+    pub remaining: String,
+}
+
+impl ParseError {
+    /// Creates an error for `input` where parsing stopped at `remaining`.
+    ///
+    /// `remaining` MUST be a suffix of `input`.
+    pub fn at(input: &str, remaining: &str) -> Self {
+        assert!(input.ends_with(remaining), "remaining is not part of input");
+        let consumed = &input[..input.len() - remaining.len()];
+
+        let line_start = consumed.rfind('\n').map(|p| p + 1).unwrap_or(0);
+
+        Self {
+            line: consumed.matches('\n').count() + 1,
+            column: consumed[line_start..].chars().count() + 1,
+            remaining: remaining.to_string(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Parsing failed at line {}, column {}: {:?}",
+            self.line,
+            self.column,
+            self.remaining.trim_start().lines().next().unwrap_or("")
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Runs `parser` over `input`, which has to be consumed completely.
+pub fn parse_all<'a, O, P>(input: &'a str, mut parser: P) -> Result<O, ParseError>
+where
+    P: nom::Parser<&'a str, O, nom::error::Error<&'a str>>,
+{
+    match parser.parse(input) {
+        Ok(("", value)) => Ok(value),
+        Ok((rest, _)) => Err(ParseError::at(input, rest)),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(ParseError::at(input, e.input)),
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::at(input, "")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nom::{
+        bytes::complete::tag, character::complete::line_ending, multi::separated_list1,
+        sequence::preceded,
+    };
+
+    #[test]
+    fn test_position() {
+        assert_eq!(
+            ParseError::at("abc\ndef", "ef"),
+            ParseError {
+                line: 2,
+                column: 2,
+                remaining: "ef".into()
+            }
+        );
+        assert_eq!(ParseError::at("abc", "abc").line, 1);
+        assert_eq!(ParseError::at("abc", "abc").column, 1);
+        assert_eq!(ParseError::at("abc\n", "").line, 2);
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let numbers = |s| {
+            parse_all(
+                s,
+                separated_list1(
+                    line_ending,
+                    preceded(tag("n="), nom::character::complete::u32),
+                ),
+            )
+        };
+
+        assert_eq!(numbers("n=1\nn=2"), Ok(vec![1, 2]));
+
+        let err = numbers("n=1\nn=2\nn=").expect_err("truncated input");
+        assert_eq!(err.remaining, "\nn=");
+        assert_eq!((err.line, err.column), (2, 4));
+
+        let err = numbers("x=1").expect_err("invalid input");
+        assert_eq!(err.remaining, "x=1");
+        assert_eq!(
+            err.to_string(),
+            "Parsing failed at line 1, column 1: \"x=1\""
+        );
+    }
+}
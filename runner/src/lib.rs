@@ -0,0 +1,59 @@
+use std::{fmt::Display, io::Read};
+
+/// Reads the puzzle input selected by `arg`:
+///   - `None` uses the `embedded` input (generally `include_str!("../input.txt")`)
+///   - `Some("-")` reads everything from stdin
+///   - `Some(path)` reads the given file
+pub fn read_input(arg: Option<&str>, embedded: &str) -> std::io::Result<String> {
+    match arg {
+        None => Ok(embedded.to_string()),
+        Some("-") => {
+            let mut s = String::new();
+            std::io::stdin().read_to_string(&mut s)?;
+            Ok(s)
+        }
+        Some(path) => std::fs::read_to_string(path),
+    }
+}
+
+/// Loads the puzzle input based on the first command line argument.
+///
+/// Panics if the input cannot be read.
+pub fn load_input(embedded: &str) -> String {
+    let arg = std::env::args().nth(1);
+    read_input(arg.as_deref(), embedded)
+        .unwrap_or_else(|e| panic!("Failed to read input {:?}: {}", arg, e))
+}
+
+/// Runs both puzzle parts against the input selected on the command line
+/// and prints the results.
+pub fn run<F1, F2, R1, R2>(embedded: &str, part1: F1, part2: F2)
+where
+    F1: FnOnce(&str) -> R1,
+    F2: FnOnce(&str) -> R2,
+    R1: Display,
+    R2: Display,
+{
+    let input = load_input(embedded);
+
+    let s1 = part1(&input);
+    println!("Part 1: {}", s1);
+
+    let s2 = part2(&input);
+    println!("Part 2: {}", s2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_input() {
+        assert_eq!(read_input(None, "embedded").unwrap(), "embedded");
+        assert_eq!(
+            read_input(Some(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")), "").unwrap(),
+            include_str!("../Cargo.toml")
+        );
+        assert!(read_input(Some("/does/not/exist.txt"), "").is_err());
+    }
+}
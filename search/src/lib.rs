@@ -0,0 +1,401 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    hash::Hash,
+};
+
+/// Finds the minimal cost to go from `start` to any state for which `goal` returns true.
+///
+/// `successors` returns the states reachable from a given state together with
+/// the cost of moving there.
+///
+/// Returns None if no goal state is reachable.
+pub fn dijkstra_cost<S, FN, IN, FG>(start: &S, mut successors: FN, mut goal: FG) -> Option<usize>
+where
+    S: Clone + Eq + Hash + Ord,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, usize)>,
+    FG: FnMut(&S) -> bool,
+{
+    let mut best: HashMap<S, usize> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+
+    best.insert(start.clone(), 0);
+    heap.push(Reverse((0, start.clone())));
+
+    while let Some(Reverse((cost, state))) = heap.pop() {
+        if goal(&state) {
+            return Some(cost);
+        }
+
+        if best.get(&state).map(|b| *b < cost).unwrap_or(false) {
+            // already found a better way here
+            continue;
+        }
+
+        for (next, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+            if best.get(&next).map(|b| *b <= next_cost).unwrap_or(false) {
+                continue;
+            }
+            best.insert(next.clone(), next_cost);
+            heap.push(Reverse((next_cost, next)));
+        }
+    }
+
+    None
+}
+
+/// Same as [dijkstra_cost], however also returns the states visited on the
+/// way, starting with `start` and ending with the reached goal.
+pub fn dijkstra_path<S, FN, IN, FG>(
+    start: &S,
+    mut successors: FN,
+    mut goal: FG,
+) -> Option<(usize, Vec<S>)>
+where
+    S: Clone + Eq + Hash + Ord,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, usize)>,
+    FG: FnMut(&S) -> bool,
+{
+    let mut best: HashMap<S, usize> = HashMap::new();
+    let mut previous: HashMap<S, S> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+
+    best.insert(start.clone(), 0);
+    heap.push(Reverse((0, start.clone())));
+
+    while let Some(Reverse((cost, state))) = heap.pop() {
+        if goal(&state) {
+            let mut path = vec![state];
+            while let Some(p) = previous.get(path.last().expect("path is not empty")) {
+                path.push(p.clone());
+            }
+            path.reverse();
+            return Some((cost, path));
+        }
+
+        if best.get(&state).map(|b| *b < cost).unwrap_or(false) {
+            // already found a better way here
+            continue;
+        }
+
+        for (next, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+            if best.get(&next).map(|b| *b <= next_cost).unwrap_or(false) {
+                continue;
+            }
+            best.insert(next.clone(), next_cost);
+            previous.insert(next.clone(), state.clone());
+            heap.push(Reverse((next_cost, next)));
+        }
+    }
+
+    None
+}
+
+/// Same as [dijkstra_cost], but guided by `heuristic`: a lower bound of the
+/// remaining cost from a state to the goal.
+///
+/// The heuristic MUST NOT overestimate the remaining cost, otherwise the
+/// returned cost may not be minimal.
+pub fn astar_cost<S, FN, IN, FH, FG>(
+    start: &S,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut goal: FG,
+) -> Option<usize>
+where
+    S: Clone + Eq + Hash + Ord,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, usize)>,
+    FH: FnMut(&S) -> usize,
+    FG: FnMut(&S) -> bool,
+{
+    let mut best: HashMap<S, usize> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+
+    best.insert(start.clone(), 0);
+    heap.push(Reverse((heuristic(start), 0, start.clone())));
+
+    while let Some(Reverse((_, cost, state))) = heap.pop() {
+        if goal(&state) {
+            return Some(cost);
+        }
+
+        if best.get(&state).map(|b| *b < cost).unwrap_or(false) {
+            // already found a better way here
+            continue;
+        }
+
+        for (next, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+            if best.get(&next).map(|b| *b <= next_cost).unwrap_or(false) {
+                continue;
+            }
+            best.insert(next.clone(), next_cost);
+            heap.push(Reverse((next_cost + heuristic(&next), next_cost, next)));
+        }
+    }
+
+    None
+}
+
+/// Finds the longest path from `start` to `end` that does not visit any
+/// node twice.
+///
+/// `edges` maps every node to its neighbours and the distance to them. This is
+/// an exhaustive search, so it is only usable on small (e.g. contracted) graphs.
+///
+/// Returns None if `end` is not reachable.
+pub fn longest_simple_path<N>(
+    edges: &HashMap<N, Vec<(N, usize)>>,
+    start: N,
+    end: N,
+) -> Option<usize>
+where
+    N: Copy + Eq + Hash,
+{
+    longest_simple_path_counted(edges, start, end).0
+}
+
+/// Same as [longest_simple_path], however also returns the number of search
+/// states (node visits) needed to find the path.
+pub fn longest_simple_path_counted<N>(
+    edges: &HashMap<N, Vec<(N, usize)>>,
+    start: N,
+    end: N,
+) -> (Option<usize>, usize)
+where
+    N: Copy + Eq + Hash,
+{
+    let mut search = SimplePathSearch::new(edges, end, false);
+    search.visit(start, 0);
+    (search.best, search.states)
+}
+
+/// Same as [longest_simple_path_counted], however skips paths that cannot beat
+/// the best path found so far. Still returns the longest path.
+///
+/// The bound assumes every remaining step enters an unvisited node using its
+/// longest incoming edge. Neighbours are tried in the order given by `edges`,
+/// so listing longer edges first finds a good path early and prunes more.
+pub fn longest_simple_path_pruned<N>(
+    edges: &HashMap<N, Vec<(N, usize)>>,
+    start: N,
+    end: N,
+) -> (Option<usize>, usize)
+where
+    N: Copy + Eq + Hash,
+{
+    let mut search = SimplePathSearch::new(edges, end, true);
+    search.visit(start, 0);
+    (search.best, search.states)
+}
+
+/// Depth-first search for the longest simple path
+struct SimplePathSearch<'a, N> {
+    edges: &'a HashMap<N, Vec<(N, usize)>>,
+    end: N,
+    prune: bool,
+
+    // Largest distance to enter every node, used to bound how much longer
+    // a path can get
+    entry: HashMap<N, usize>,
+    remaining: usize,
+
+    visited: HashSet<N>,
+    route: Vec<N>,
+    best: Option<usize>,
+    best_route: Vec<N>,
+    states: usize,
+}
+
+impl<'a, N> SimplePathSearch<'a, N>
+where
+    N: Copy + Eq + Hash,
+{
+    fn new(edges: &'a HashMap<N, Vec<(N, usize)>>, end: N, prune: bool) -> Self {
+        let mut entry: HashMap<N, usize> = HashMap::new();
+        if prune {
+            for (b, d) in edges.values().flatten() {
+                let e = entry.entry(*b).or_default();
+                *e = (*e).max(*d);
+            }
+        }
+        let remaining = entry.values().sum();
+
+        Self {
+            edges,
+            end,
+            prune,
+            entry,
+            remaining,
+            visited: HashSet::new(),
+            route: Vec::new(),
+            best: None,
+            best_route: Vec::new(),
+            states: 0,
+        }
+    }
+
+    fn visit(&mut self, current: N, so_far: usize) {
+        self.states += 1;
+        self.route.push(current);
+        self.visit_from(current, so_far);
+        self.route.pop();
+    }
+
+    fn visit_from(&mut self, current: N, so_far: usize) {
+        if current == self.end {
+            if self.best.map(|b| b < so_far).unwrap_or(true) {
+                self.best = Some(so_far);
+                self.best_route.clone_from(&self.route);
+            }
+            return;
+        }
+
+        if self.prune {
+            if let Some(best) = self.best {
+                if so_far + self.remaining <= best {
+                    return;
+                }
+            }
+        }
+
+        self.visited.insert(current);
+        let entry = self.entry.get(&current).copied().unwrap_or(0);
+        self.remaining -= entry;
+
+        let edges = self.edges;
+        for (n, d) in edges.get(&current).into_iter().flatten() {
+            if !self.visited.contains(n) {
+                self.visit(*n, so_far + d);
+            }
+        }
+
+        self.remaining += entry;
+        self.visited.remove(&current);
+    }
+}
+
+/// Same as [longest_simple_path], however also returns the nodes of the
+/// path, starting with `start` and ending with `end`.
+pub fn longest_simple_route<N>(
+    edges: &HashMap<N, Vec<(N, usize)>>,
+    start: N,
+    end: N,
+) -> Option<(usize, Vec<N>)>
+where
+    N: Copy + Eq + Hash,
+{
+    let mut search = SimplePathSearch::new(edges, end, false);
+    search.visit(start, 0);
+    search.best.map(|d| (d, search.best_route))
+}
+
+/// Same as [longest_simple_route], pruned like [longest_simple_path_pruned].
+pub fn longest_simple_route_pruned<N>(
+    edges: &HashMap<N, Vec<(N, usize)>>,
+    start: N,
+    end: N,
+) -> Option<(usize, Vec<N>)>
+where
+    N: Copy + Eq + Hash,
+{
+    let mut search = SimplePathSearch::new(edges, end, true);
+    search.visit(start, 0);
+    search.best.map(|d| (d, search.best_route))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dijkstra_cost() {
+        // walk on a number line, where moving +1 costs 1 and +3 costs 2
+        assert_eq!(
+            dijkstra_cost(&0, |n| [(n + 1, 1), (n + 3, 2)], |n| *n == 10),
+            Some(7)
+        );
+
+        assert_eq!(
+            dijkstra_cost(
+                &0,
+                |n| if *n < 5 { vec![(n + 1, 1)] } else { vec![] },
+                |n| *n == 10
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_dijkstra_path() {
+        assert_eq!(
+            dijkstra_path(&0, |n| [(n + 1, 1), (n + 3, 2)], |n| *n == 6),
+            Some((4, vec![0, 3, 6]))
+        );
+        assert_eq!(
+            dijkstra_path(&0, |n| [(n + 1, 1)], |n| *n == 0),
+            Some((0, vec![0]))
+        );
+        assert_eq!(
+            dijkstra_path(
+                &0,
+                |n| if *n < 5 { vec![(n + 1, 1)] } else { vec![] },
+                |n| *n == 10
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_astar_cost() {
+        let successors = |n: &i32| [(n + 1, 1), (n + 3, 2), (n - 1, 1)];
+        let heuristic = |n: &i32| (10 - n).max(0) as usize / 3;
+
+        assert_eq!(
+            astar_cost(&0, successors, heuristic, |n| *n == 10),
+            dijkstra_cost(&0, successors, |n| *n == 10)
+        );
+        assert_eq!(astar_cost(&0, successors, heuristic, |n| *n == 10), Some(7));
+    }
+
+    #[test]
+    fn test_longest_simple_path() {
+        let edges: HashMap<char, Vec<(char, usize)>> = [
+            ('a', vec![('b', 1), ('c', 5)]),
+            ('b', vec![('a', 1), ('c', 1), ('d', 10)]),
+            ('c', vec![('a', 5), ('b', 1), ('d', 1)]),
+            ('d', vec![('b', 10), ('c', 1)]),
+        ]
+        .into_iter()
+        .collect();
+
+        // a -> c -> b -> d
+        assert_eq!(longest_simple_path(&edges, 'a', 'd'), Some(16));
+        assert_eq!(longest_simple_path(&edges, 'a', 'a'), Some(0));
+        assert_eq!(longest_simple_path(&edges, 'a', 'x'), None);
+
+        let (exact, exact_states) = longest_simple_path_counted(&edges, 'a', 'd');
+        let (pruned, pruned_states) = longest_simple_path_pruned(&edges, 'a', 'd');
+        assert_eq!(exact, Some(16));
+        assert_eq!(pruned, Some(16));
+        assert!(pruned_states <= exact_states);
+        assert_eq!(longest_simple_path_pruned(&edges, 'a', 'x').0, None);
+
+        assert_eq!(
+            longest_simple_route(&edges, 'a', 'd'),
+            Some((16, vec!['a', 'c', 'b', 'd']))
+        );
+        assert_eq!(longest_simple_route(&edges, 'a', 'a'), Some((0, vec!['a'])));
+        assert_eq!(longest_simple_route(&edges, 'a', 'x'), None);
+        assert_eq!(
+            longest_simple_route_pruned(&edges, 'a', 'd'),
+            Some((16, vec!['a', 'c', 'b', 'd']))
+        );
+        assert_eq!(longest_simple_route_pruned(&edges, 'a', 'x'), None);
+    }
+}
@@ -16,9 +16,9 @@ fn main() {
         .with(EnvFilter::from_default_env())
         .init();
 
-    let s1 = {{project-name}}::part1(include_str!("../input.txt"));
-    println!("Part 1: {}", s1);
-
-    let s2 = {{project-name}}::part2(include_str!("../input.txt"));
-    println!("Part 2: {}", s2);
+    runner::run(
+        include_str!("../input.txt"),
+        {{project-name}}::part1,
+        {{project-name}}::part2,
+    );
 }